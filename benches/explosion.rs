@@ -0,0 +1,85 @@
+//! Compara el motor de explosiones con la versión recursiva anterior en laberintos de
+//! 1000x1000. Se ejecuta con `cargo bench`.
+
+use bomberman_r::bomberman::detonar_bomba;
+use bomberman_r::bomberman::model::laberinto::Laberinto;
+use bomberman_r::bomberman::model::objeto::Objeto;
+use std::time::{Duration, Instant};
+
+const TAMANO: usize = 1000;
+
+// Laberinto de `TAMANO` x `TAMANO` con `bombas` bombas de alcance 1 seguidas en la primera
+// fila (continuando en las siguientes), que explotan todas en cadena.
+fn laberinto_con_cadena(bombas: usize) -> Laberinto {
+    let mut texto = String::new();
+    for y in 0..TAMANO {
+        let fila: Vec<&str> = (0..TAMANO)
+            .map(|x| if y * TAMANO + x < bombas { "B1" } else { "_" })
+            .collect();
+        texto.push_str(&fila.join(" "));
+        texto.push('\n');
+    }
+    texto.parse().expect("laberinto de prueba inválido")
+}
+
+// Versión recursiva anterior, que reserva una matriz de visitados por cada bomba encadenada.
+fn detonar_recursivo(laberinto: &mut Laberinto, x: usize, y: usize, alcance: i32) {
+    laberinto.grid[y][x].objeto = Objeto::Vacio;
+    let mut visited = vec![vec![false; laberinto.ancho]; laberinto.alto];
+    visited[y][x] = true;
+    for &(dx, dy) in &[(1i32, 0i32), (-1, 0), (0, 1), (0, -1)] {
+        let mut new_x = x;
+        let mut new_y = y;
+        for _n in 1..=alcance {
+            new_x = new_x.wrapping_add(dx as usize);
+            new_y = new_y.wrapping_add(dy as usize);
+            if new_x >= laberinto.ancho || new_y >= laberinto.alto {
+                break;
+            }
+            if laberinto.grid[new_y][new_x].objeto == Objeto::Pared {
+                break;
+            }
+            visited[new_y][new_x] = true;
+            if let Objeto::Bomba(alcance) = laberinto.grid[new_y][new_x].objeto {
+                detonar_recursivo(laberinto, new_x, new_y, alcance);
+            }
+        }
+    }
+}
+
+fn medir(nombre: &str, mut f: impl FnMut()) -> Duration {
+    let inicio = Instant::now();
+    f();
+    let duracion = inicio.elapsed();
+    println!("{:<45} {:>10.2?}", nombre, duracion);
+    duracion
+}
+
+fn main() {
+    for bombas in [50, 200] {
+        let original = laberinto_con_cadena(bombas);
+
+        let mut laberinto = original.clone();
+        let anterior = medir(&format!("recursivo, cadena de {} bombas", bombas), || {
+            detonar_recursivo(&mut laberinto, 0, 0, 1)
+        });
+
+        let mut laberinto = original.clone();
+        let actual = medir(&format!("iterativo, cadena de {} bombas", bombas), || {
+            detonar_bomba(&mut laberinto, 0, 0).expect("coordenadas válidas");
+        });
+
+        println!(
+            "{:<45} {:>9.1}x",
+            "mejora",
+            anterior.as_secs_f64() / actual.as_secs_f64()
+        );
+    }
+
+    // La versión recursiva desborda la pila (y necesitaría un terabyte de matrices) con
+    // una cadena que recorre todo el laberinto.
+    let mut laberinto = laberinto_con_cadena(TAMANO * TAMANO);
+    medir("iterativo, cadena de 1000000 bombas", || {
+        detonar_bomba(&mut laberinto, 0, 0).expect("coordenadas válidas");
+    });
+}
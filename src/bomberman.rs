@@ -1,11 +1,21 @@
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::Path;
+pub mod aleatorio;
+pub mod analisis;
+pub mod enemigo;
+pub mod generador;
+pub mod jugador;
+pub mod lote;
 pub mod model;
+pub mod simulacion;
 use model::celda::Celda;
 use model::direccion::Direccion;
+use model::evento::EventoExplosion;
 use model::laberinto::Laberinto;
 use model::objeto::Objeto;
+use model::patron::Patron;
 
 fn quitar_vida_enemigo(actual: &mut Celda) {
     if let Objeto::Enemigo(mut vidas) = actual.objeto {
@@ -22,12 +32,7 @@ fn quitar_vida_enemigo(actual: &mut Celda) {
 
 fn obtener_direccion_del_desvio(celda: &Celda) -> Option<(i32, i32)> {
     if let Objeto::Desvio(direccion) = &celda.objeto {
-        match direccion {
-            Direccion::Izquierda => Some((-1, 0)),
-            Direccion::Derecha => Some((1, 0)),
-            Direccion::Arriba => Some((0, -1)),
-            Direccion::Abajo => Some((0, 1)),
-        }
+        Some(direccion.desplazamiento())
     } else {
         None
     }
@@ -35,7 +40,8 @@ fn obtener_direccion_del_desvio(celda: &Celda) -> Option<(i32, i32)> {
 /// Detona una bomba en el laberinto y causa una explosión.
 ///
 /// Esta función permite detonar una bomba en las coordenadas `(x, y)` del laberinto.
-/// La explosión se propaga en cuatro direcciones: izquierda, derecha, arriba y abajo.
+/// La explosión se propaga en cuatro direcciones: izquierda, derecha, arriba y abajo, salvo
+/// que la bomba tenga otro [`Patron`](model::patron::Patron).
 /// El alcance de la explosión está determinado por la potencia de la bomba, que puede ser de
 /// dos tipos: bomba normal o bomba de traspaso. Si una bomba de traspaso alcanza una pared o
 /// una roca, seguirá explotando al otro lado.
@@ -51,14 +57,15 @@ fn obtener_direccion_del_desvio(celda: &Celda) -> Option<(i32, i32)> {
 ///
 /// # Ejemplo
 ///
-/// ```rust
-/// use laberinto::{detonar_bomba, cargar_laberinto, Laberinto};
+/// ```no_run
+/// use bomberman_r::bomberman::detonar_bomba;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
 ///
-/// let mut laberinto = cargar_laberinto("laberinto.txt");
+/// let laberinto = Laberinto::cargar("laberinto.txt");
 ///
-/// if let Ok(ref mut laberinto) = laberinto {
+/// if let Ok(mut laberinto) = laberinto {
 ///     // Detonar una bomba en la coordenada (4, 2) del laberinto
-///     detonar_bomba(laberinto, 4, 2);
+///     let _ = detonar_bomba(&mut laberinto, 4, 2);
 ///
 ///     // El laberinto ha sido modificado después de la explosión.
 /// } else {
@@ -68,99 +75,529 @@ fn obtener_direccion_del_desvio(celda: &Celda) -> Option<(i32, i32)> {
 ///
 /// # Notas
 ///
-/// - Si las coordenadas `(x, y)` están fuera de los límites del laberinto, la función
-///   imprimirá un mensaje de advertencia y no realizará ninguna acción.
+/// - Si en `(x, y)` no hay una bomba, el laberinto no cambia.
 /// - La explosión afecta a las celdas adyacentes dentro del alcance de la bomba.
 /// - Las celdas afectadas por la explosión pueden cambiar su contenido, destruyendo enemigos
 ///   y objetos.
 ///
 /// # Errores
 ///
-/// Esta función no devuelve errores directamente, pero modifica el estado del laberinto.
-/// Para verificar posibles errores, es importante verificar el estado del laberinto después
-/// de llamar a esta función.
-
+/// Devuelve un error si las coordenadas `(x, y)` están fuera de los límites del laberinto. En
+/// ese caso no se imprime nada y el laberinto no cambia.
 pub fn detonar_bomba(laberinto: &mut Laberinto, x: usize, y: usize) -> Result<(), io::Error> {
-    if x >= laberinto.tamano || y >= laberinto.tamano {
-        println!("Fuera de los parámetros del laberinto\n");
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "Fuera de los parámetros del laberinto",
-        ));
-    }
+    detonar(laberinto, x, y, None)
+}
+
+/// Detona una bomba igual que [`detonar_bomba`] y devuelve los eventos de la explosión.
+///
+/// Los eventos están en el orden en que ocurrieron: celdas alcanzadas, enemigos dañados o
+/// eliminados, bombas encadenadas (con su profundidad), desvíos y bloqueos por paredes o rocas.
+/// Si en `(x, y)` no hay una bomba, la lista está vacía.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::detonar_bomba_con_traza;
+/// use bomberman_r::bomberman::model::evento::EventoExplosion;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
+///
+/// let mut laberinto: Laberinto = "B1 F1".parse().unwrap();
+/// let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+/// assert!(eventos.contains(&EventoExplosion::EnemigoEliminado { x: 1, y: 0 }));
+/// ```
+pub fn detonar_bomba_con_traza(
+    laberinto: &mut Laberinto,
+    x: usize,
+    y: usize,
+) -> Result<Vec<EventoExplosion>, io::Error> {
+    let mut eventos = Vec::new();
+    detonar(laberinto, x, y, Some(&mut eventos))?;
+    Ok(eventos)
+}
 
-    let objeto = &laberinto.grid[y][x].objeto;
+/// Forma de resolver varias detonaciones en una misma ejecución.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ModoDetonacion {
+    /// Las bombas se detonan una después de otra, cada una sobre el laberinto que dejó la
+    /// anterior. Si una bomba ya explotó en la cadena de otra, su detonación no hace nada.
+    Secuencial,
+
+    /// Todas las bombas explotan a la vez a partir del mismo laberinto inicial. Una bomba
+    /// alcanzada por varias explosiones explota una sola vez, y cada enemigo pierde una vida
+    /// por cada bomba distinta cuya explosión lo alcanza, aunque varias detonaciones la hayan
+    /// encadenado.
+    Simultaneo,
+}
+
+/// Detona las bombas en las coordenadas `(x, y)` de `coordenadas` según `modo`.
+///
+/// Todas las coordenadas se verifican antes de detonar ninguna bomba, así que si alguna está
+/// fuera del laberinto se devuelve un error sin modificarlo. Las coordenadas que no tienen una
+/// bomba se ignoran, igual que en [`detonar_bomba`].
+///
+/// Los modos difieren cuando hay cajas: en modo secuencial una caja rota por una bomba ya no
+/// detiene a las siguientes, mientras que en modo simultáneo detiene a todas. Además, en modo
+/// simultáneo todas las bombas indicadas tienen profundidad 0.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::{detonar_bombas, ModoDetonacion};
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
+///
+/// let mut laberinto: Laberinto = "B1 F2 B1".parse().unwrap();
+/// detonar_bombas(&mut laberinto, &[(0, 0), (2, 0)], ModoDetonacion::Simultaneo).unwrap();
+/// assert_eq!(laberinto.to_string(), "_ _ _");
+/// ```
+pub fn detonar_bombas(
+    laberinto: &mut Laberinto,
+    coordenadas: &[(usize, usize)],
+    modo: ModoDetonacion,
+) -> Result<(), io::Error> {
+    detonar_varias(laberinto, coordenadas, modo, None)
+}
+
+/// Detona varias bombas igual que [`detonar_bombas`] y devuelve los eventos de todas las
+/// explosiones, en orden.
+pub fn detonar_bombas_con_traza(
+    laberinto: &mut Laberinto,
+    coordenadas: &[(usize, usize)],
+    modo: ModoDetonacion,
+) -> Result<Vec<EventoExplosion>, io::Error> {
+    let mut eventos = Vec::new();
+    detonar_varias(laberinto, coordenadas, modo, Some(&mut eventos))?;
+    Ok(eventos)
+}
 
-    // Verificar si la bomba que inicia la explosión es de traspaso
-    let es_bomba_de_traspaso = matches!(objeto, Objeto::BombaTraspaso(_));
+fn detonar_varias(
+    laberinto: &mut Laberinto,
+    coordenadas: &[(usize, usize)],
+    modo: ModoDetonacion,
+    mut traza: Option<&mut Vec<EventoExplosion>>,
+) -> Result<(), io::Error> {
+    for &(x, y) in coordenadas {
+        verificar_coordenadas(laberinto, x, y)?;
+    }
 
-    match objeto {
-        Objeto::Bomba(alcance) | Objeto::BombaTraspaso(alcance) => {
-            detonar_bomba_recursive(laberinto, x, y, *alcance, es_bomba_de_traspaso);
+    match modo {
+        ModoDetonacion::Secuencial => {
+            for &(x, y) in coordenadas {
+                detonar(laberinto, x, y, traza.as_deref_mut())?;
+            }
+        }
+        ModoDetonacion::Simultaneo => {
+            // Se encienden todas antes de propagar ninguna explosión
+            let mut pila: Vec<Frente> = coordenadas
+                .iter()
+                .filter_map(|&(x, y)| encender_bomba(laberinto, x, y, 0, &mut traza))
+                .collect();
+            pila.reverse(); // La primera bomba indicada se propaga primero
+            propagar_explosion(laberinto, pila, &mut traza);
         }
-        _ => return Ok(()), // No es una bomba, no hacemos nada
     }
-    laberinto.grid[y][x].objeto = Objeto::Vacio;
     Ok(())
 }
 
-fn detonar_bomba_recursive(
+fn verificar_coordenadas(laberinto: &Laberinto, x: usize, y: usize) -> Result<(), io::Error> {
+    if x >= laberinto.ancho || y >= laberinto.alto {
+        return Err(io::Error::other("Fuera de los parámetros del laberinto"));
+    }
+    Ok(())
+}
+
+fn detonar(
+    laberinto: &mut Laberinto,
+    x: usize,
+    y: usize,
+    mut traza: Option<&mut Vec<EventoExplosion>>,
+) -> Result<(), io::Error> {
+    verificar_coordenadas(laberinto, x, y)?;
+
+    // Si no es una bomba, no hacemos nada
+    if let Some(frente) = encender_bomba(laberinto, x, y, 0, &mut traza) {
+        propagar_explosion(laberinto, vec![frente], &mut traza);
+    }
+    Ok(())
+}
+
+// Si en `(x, y)` hay una bomba, la quita del laberinto y devuelve el frente de su explosión.
+fn encender_bomba(
     laberinto: &mut Laberinto,
+    x: usize,
+    y: usize,
+    profundidad: usize,
+    traza: &mut Option<&mut Vec<EventoExplosion>>,
+) -> Option<Frente> {
+    let (ancho, alto) = (laberinto.ancho, laberinto.alto);
+    let celda = &mut laberinto.grid[y][x];
+
+    // También indica si la bomba que inicia la explosión es de traspaso
+    let (alcance, es_bomba_de_traspaso) = celda.objeto.como_bomba()?;
+    let patron = celda.objeto.patron().unwrap_or(Patron::Cruz);
+
+    registrar(traza, EventoExplosion::BombaActivada { x, y, profundidad });
+    // Un jugador parado sobre su propia bomba muere con ella
+    if let Objeto::Jugador(jugador, _) = celda.objeto {
+        registrar(traza, EventoExplosion::JugadorEliminado { x, y, jugador });
+    }
+    celda.objeto = Objeto::Vacio;
+    Some(Frente::new(
+        (x, y),
+        alcance,
+        es_bomba_de_traspaso,
+        patron,
+        profundidad,
+        (ancho, alto),
+    ))
+}
+
+fn registrar(traza: &mut Option<&mut Vec<EventoExplosion>>, evento: EventoExplosion) {
+    if let Some(eventos) = traza {
+        eventos.push(evento);
+    }
+}
+
+// Estado de la explosión de una bomba mientras recorre las direcciones de su patrón, o las
+// celdas de su cuadrado.
+struct Frente {
     x: usize,
     y: usize,
     alcance: i32,
     es_bomba_de_traspaso: bool,
+    profundidad: usize,
+    // Celdas ya alcanzadas por esta bomba, que daña cada celda a lo sumo una vez
+    visitadas: HashSet<(usize, usize)>,
+    // Desvíos atravesados en la dirección actual, para detectar ciclos
+    desvios: HashSet<(usize, usize)>,
+    // Direcciones del patrón de la bomba e índice de la que se está recorriendo
+    direcciones: &'static [Direccion],
+    direccion: usize,
+    // Pasos recorridos en la dirección actual, posición alcanzada y sentido (puede cambiar
+    // por un desvío)
+    paso: i32,
+    pos_x: usize,
+    pos_y: usize,
+    dx: i32,
+    dy: i32,
+    // Celdas que faltan recorrer, si el patrón es un cuadrado
+    area: Option<Area>,
+}
+
+impl Frente {
+    fn new(
+        (x, y): (usize, usize),
+        alcance: i32,
+        es_bomba_de_traspaso: bool,
+        patron: Patron,
+        profundidad: usize,
+        (ancho, alto): (usize, usize),
+    ) -> Self {
+        let direcciones = patron.direcciones();
+        let (dx, dy) = direcciones
+            .first()
+            .map_or((0, 0), |direccion| direccion.desplazamiento());
+        Frente {
+            x,
+            y,
+            alcance,
+            es_bomba_de_traspaso,
+            profundidad,
+            visitadas: HashSet::from([(x, y)]),
+            desvios: HashSet::new(),
+            direcciones,
+            direccion: 0,
+            paso: 0,
+            pos_x: x,
+            pos_y: y,
+            dx,
+            dy,
+            area: (patron == Patron::Cuadrado).then(|| Area::new(x, y, alcance, ancho, alto)),
+        }
+    }
+
+    // Pasa a la siguiente dirección, volviendo a partir desde la bomba
+    fn siguiente_direccion(&mut self) {
+        self.direccion += 1;
+        self.paso = 0;
+        self.pos_x = self.x;
+        self.pos_y = self.y;
+        self.desvios.clear();
+        if let Some(direccion) = self.direcciones.get(self.direccion) {
+            (self.dx, self.dy) = direccion.desplazamiento();
+        }
+    }
+}
+
+// Recorrido fila por fila de las celdas del laberinto que están dentro del cuadrado de una
+// bomba.
+struct Area {
+    desde_x: usize,
+    hasta_x: usize,
+    hasta_y: usize,
+    x: usize,
+    y: usize,
+}
+
+impl Area {
+    fn new(x: usize, y: usize, alcance: i32, ancho: usize, alto: usize) -> Self {
+        let radio = alcance.max(0) as usize;
+        Area {
+            desde_x: x.saturating_sub(radio),
+            hasta_x: x.saturating_add(radio).min(ancho - 1),
+            hasta_y: y.saturating_add(radio).min(alto - 1),
+            x: x.saturating_sub(radio),
+            y: y.saturating_sub(radio),
+        }
+    }
+
+    fn siguiente(&mut self) -> Option<(usize, usize)> {
+        if self.y > self.hasta_y {
+            return None;
+        }
+        let celda = (self.x, self.y);
+        if self.x == self.hasta_x {
+            self.x = self.desde_x;
+            self.y += 1;
+        } else {
+            self.x += 1;
+        }
+        Some(celda)
+    }
+}
+
+// Propaga las explosiones de las bombas de `pila`, ya encendidas, y de todas las que
+// encadenan. La bomba del tope de la pila se propaga primero.
+//
+// Un desvío cambia la dirección de la explosión sin gastar alcance; si la celda siguiente es
+// otro desvío, se aplica también. La explosión se detiene si un desvío la saca del laberinto,
+// o si la devuelve a un desvío que ya atravesó en la misma dirección: seguir sólo repetiría
+// celdas ya alcanzadas por esta bomba.
+//
+// Un teletransportador gasta un paso de alcance, como cualquier celda, y la explosión sigue
+// desde su pareja en la misma dirección.
+//
+// Una caja detiene la explosión y pierde resistencia. Las cajas que se rompen siguen
+// deteniendo a las demás explosiones de la pila, porque todas ocurren en el mismo instante;
+// recién al terminar se reemplazan por el objeto que escondían.
+//
+// Las bombas encadenadas se resuelven con una pila explícita en lugar de recursión: cuando la
+// explosión alcanza otra bomba, ésta se apila y explota por completo antes de que la anterior
+// siga avanzando, en el mismo orden que una búsqueda en profundidad. Cada bomba guarda sólo
+// las celdas que alcanzó, en lugar de una matriz del tamaño del laberinto.
+fn propagar_explosion(
+    laberinto: &mut Laberinto,
+    mut pila: Vec<Frente>,
+    traza: &mut Option<&mut Vec<EventoExplosion>>,
 ) {
-    laberinto.grid[y][x].objeto = Objeto::Vacio;
-    let mut visited = vec![vec![false; laberinto.tamano]; laberinto.tamano];
-    visited[y][x] = true;
-    for &(mut dx, mut dy) in &[(1, 0), (-1, 0), (0, 1), (0, -1)] {
-        let mut new_x = x;
-        let mut new_y = y;
-        for _n in 1..=alcance {
-            new_x = new_x.wrapping_add(dx as usize);
-            new_y = new_y.wrapping_add(dy as usize);
-
-            if new_x >= laberinto.tamano || new_y >= laberinto.tamano {
-                break; // Salir del bucle si estamos fuera del laberinto o ya visitamos esta celda
-            }
+    let mut cajas_rotas = Vec::new();
+    // Parejas de los teletransportadores alcanzados, para buscar cada una una sola vez
+    let mut parejas: HashMap<(usize, usize), Option<(usize, usize)>> = HashMap::new();
 
-            if let Some((desvio_dx, desvio_dy)) =
-                obtener_direccion_del_desvio(&laberinto.grid[new_y][new_x])
+    'pasos: while let Some(frente) = pila.last_mut() {
+        // Las bombas con patrón de cuadrado alcanzan cada celda del cuadrado sin avanzar en
+        // línea recta
+        if frente.area.is_some() {
+            let Some((x, y)) = frente.area.as_mut().and_then(Area::siguiente) else {
+                pila.pop();
+                continue;
+            };
+            let objeto = &laberinto.grid[y][x].objeto;
+            if (x, y) == (frente.x, frente.y)
+                || *objeto == Objeto::Pared
+                || (*objeto == Objeto::Roca && !frente.es_bomba_de_traspaso)
             {
-                // Aplicar el desvío a las nuevas coordenadas
-                new_x = new_x.wrapping_add(desvio_dx as usize);
-                new_y = new_y.wrapping_add(desvio_dy as usize);
-                dx = desvio_dx;
-                dy = desvio_dy;
+                continue;
             }
-
-            if (laberinto.grid[new_y][new_x].objeto == Objeto::Pared)
-                || (laberinto.grid[new_y][new_x].objeto == Objeto::Roca && !es_bomba_de_traspaso)
+            if let Some(encadenada) =
+                alcanzar_celda(laberinto, frente, x, y, &mut cajas_rotas, traza)
             {
+                pila.push(encadenada);
+            }
+            continue;
+        }
+
+        if frente.direccion >= frente.direcciones.len() {
+            pila.pop();
+            continue;
+        }
+        if frente.paso >= frente.alcance {
+            frente.siguiente_direccion();
+            continue;
+        }
+        frente.paso += 1;
+
+        let mut new_x = frente.pos_x.wrapping_add(frente.dx as usize);
+        let mut new_y = frente.pos_y.wrapping_add(frente.dy as usize);
+
+        // Aplicar los desvíos hasta llegar a una celda que no sea un desvío, verificando los
+        // límites del laberinto después de cada uno
+        loop {
+            if new_x >= laberinto.ancho || new_y >= laberinto.alto {
+                // Salir de esta dirección si estamos fuera del laberinto
+                frente.siguiente_direccion();
+                continue 'pasos;
+            }
+
+            let Some((desvio_dx, desvio_dy)) =
+                obtener_direccion_del_desvio(&laberinto.grid[new_y][new_x])
+            else {
                 break;
+            };
+
+            // El camino después de un desvío sólo depende del desvío, así que volver a uno
+            // ya atravesado repetiría el mismo recorrido hasta agotar el alcance
+            if !frente.desvios.insert((new_x, new_y)) {
+                registrar(traza, EventoExplosion::CicloDeDesvio { x: new_x, y: new_y });
+                frente.siguiente_direccion();
+                continue 'pasos;
             }
-            if !visited[new_y][new_x] {
-                quitar_vida_enemigo(&mut laberinto.grid[new_y][new_x]);
-                visited[new_y][new_x] = true;
+            if let Objeto::Desvio(direccion) = &laberinto.grid[new_y][new_x].objeto {
+                let direccion = direccion.clone();
+                registrar(
+                    traza,
+                    EventoExplosion::Desviada {
+                        x: new_x,
+                        y: new_y,
+                        direccion,
+                    },
+                );
             }
+            // Aplicar el desvío a las nuevas coordenadas
+            new_x = new_x.wrapping_add(desvio_dx as usize);
+            new_y = new_y.wrapping_add(desvio_dy as usize);
+            frente.dx = desvio_dx;
+            frente.dy = desvio_dy;
+        }
+        frente.pos_x = new_x;
+        frente.pos_y = new_y;
 
-            if let Objeto::BombaTraspaso(_) | Objeto::Bomba(_) = laberinto.grid[new_y][new_x].objeto
-            {
-                match laberinto.grid[new_y][new_x].objeto {
-                    Objeto::Bomba(alcance) => {
-                        laberinto.grid[new_y][new_x].objeto = Objeto::Vacio;
-                        detonar_bomba_recursive(laberinto, new_x, new_y, alcance, false);
-                    }
-                    Objeto::BombaTraspaso(alcance) => {
-                        laberinto.grid[new_y][new_x].objeto = Objeto::Vacio;
-                        detonar_bomba_recursive(laberinto, new_x, new_y, alcance, true);
-                    }
-                    _ => return, // No es una bomba, no hacemos nada
-                }
+        if let Objeto::Teletransportador(_) = laberinto.grid[new_y][new_x].objeto {
+            let pareja = *parejas
+                .entry((new_x, new_y))
+                .or_insert_with(|| laberinto.pareja_de_teletransportador(new_x, new_y));
+            if let Some((destino_x, destino_y)) = pareja {
+                registrar(
+                    traza,
+                    EventoExplosion::Teletransportada {
+                        x: new_x,
+                        y: new_y,
+                        destino_x,
+                        destino_y,
+                    },
+                );
+                frente.pos_x = destino_x;
+                frente.pos_y = destino_y;
+                continue;
             }
         }
+
+        let objeto = &laberinto.grid[new_y][new_x].objeto;
+        if (*objeto == Objeto::Pared) || (*objeto == Objeto::Roca && !frente.es_bomba_de_traspaso) {
+            let objeto = objeto.clone();
+            registrar(
+                traza,
+                EventoExplosion::Bloqueada {
+                    x: new_x,
+                    y: new_y,
+                    objeto,
+                },
+            );
+            frente.siguiente_direccion();
+            continue;
+        }
+
+        let es_caja = matches!(objeto, Objeto::Caja(..));
+        let encadenada = alcanzar_celda(laberinto, frente, new_x, new_y, &mut cajas_rotas, traza);
+        if es_caja {
+            frente.siguiente_direccion();
+        }
+        if let Some(encadenada) = encadenada {
+            pila.push(encadenada);
+        }
+    }
+
+    for (x, y) in cajas_rotas {
+        let celda = &mut laberinto.grid[y][x];
+        if let Objeto::Caja(_, contenido) = &mut celda.objeto {
+            celda.objeto = contenido
+                .take()
+                .map_or(Objeto::Vacio, |contenido| *contenido);
+        }
+    }
+}
+
+// Aplica a la celda `(x, y)` los efectos de la explosión de `frente`, que no la detuvo una
+// pared ni una roca. Si en la celda hay una bomba, la enciende y devuelve su frente.
+fn alcanzar_celda(
+    laberinto: &mut Laberinto,
+    frente: &mut Frente,
+    x: usize,
+    y: usize,
+    cajas_rotas: &mut Vec<(usize, usize)>,
+    traza: &mut Option<&mut Vec<EventoExplosion>>,
+) -> Option<Frente> {
+    let dimensiones = (laberinto.ancho, laberinto.alto);
+    let celda = &mut laberinto.grid[y][x];
+    registrar(traza, EventoExplosion::CeldaAlcanzada { x, y });
+
+    if let Objeto::Caja(resistencia, _) = &mut celda.objeto {
+        // Una caja ya rota conserva resistencia 0 hasta el final de la explosión
+        if frente.visitadas.insert((x, y)) && *resistencia > 0 {
+            *resistencia -= 1;
+            let evento = if *resistencia == 0 {
+                cajas_rotas.push((x, y));
+                EventoExplosion::CajaRota { x, y }
+            } else {
+                EventoExplosion::CajaDaniada {
+                    x,
+                    y,
+                    resistencia: *resistencia,
+                }
+            };
+            registrar(traza, evento);
+        }
+        return None;
+    }
+
+    if frente.visitadas.insert((x, y)) {
+        let habia_enemigo = matches!(celda.objeto, Objeto::Enemigo(_));
+        quitar_vida_enemigo(celda);
+        if habia_enemigo {
+            let evento = match celda.objeto {
+                Objeto::Enemigo(vidas) => EventoExplosion::EnemigoDaniado { x, y, vidas },
+                _ => EventoExplosion::EnemigoEliminado { x, y },
+            };
+            registrar(traza, evento);
+        }
+
+        // Las mejoras a la vista se destruyen
+        if let Objeto::Mejora(_) = celda.objeto {
+            celda.objeto = Objeto::Vacio;
+            registrar(traza, EventoExplosion::MejoraDestruida { x, y });
+        }
+
+        // El jugador muere; si estaba sobre una bomba, ésta queda en la celda y encadena
+        if let Objeto::Jugador(jugador, bomba) = &mut celda.objeto {
+            let jugador = *jugador;
+            celda.objeto = bomba.take().map_or(Objeto::Vacio, |bomba| *bomba);
+            registrar(traza, EventoExplosion::JugadorEliminado { x, y, jugador });
+        }
     }
+
+    // Las bombas temporizadas también explotan antes de tiempo
+    let (alcance, es_de_traspaso) = celda.objeto.como_bomba()?;
+    let patron = celda.objeto.patron().unwrap_or(Patron::Cruz);
+    let profundidad = frente.profundidad + 1;
+    registrar(traza, EventoExplosion::BombaActivada { x, y, profundidad });
+    celda.objeto = Objeto::Vacio;
+    Some(Frente::new(
+        (x, y),
+        alcance,
+        es_de_traspaso,
+        patron,
+        profundidad,
+        dimensiones,
+    ))
 }
 
 /// Guarda un laberinto en un archivo de texto.
@@ -172,9 +609,15 @@ fn detonar_bomba_recursive(
 /// - Enemigo: 'F' seguido del número de vidas.
 /// - Bomba: 'B' seguido del alcance.
 /// - Bomba de Traspaso: 'S' seguido del alcance.
+/// - Bomba con patrón: la bomba seguida de 'X' (equis), '*' (estrella) o '#' (cuadrado).
+/// - Bomba temporizada: cualquiera de las anteriores seguida de ':' y la mecha.
 /// - Roca: 'R'.
+/// - Caja: 'C' seguido de la resistencia y, si esconde un objeto, '+' y el objeto.
 /// - Pared: 'W'.
-/// - Desvío: 'D' seguido de la dirección ('L', 'R', 'U', 'D').
+/// - Teletransportador: 'T' seguido de su número, del 1 al 9.
+/// - Mejora: 'M' seguido de su tipo ('A' alcance, 'T' traspaso, 'B' bomba extra).
+/// - Desvío: 'D' seguido de la dirección ('L', 'R', 'U', 'D', 'NE', 'NW', 'SE', 'SW').
+/// - Jugador: 'P' seguido de su número y, si está sobre una bomba, '+' y la bomba.
 /// - Vacío: '_'.
 ///
 /// Cada fila del laberinto se representa como una línea en el archivo, y los objetos de cada fila
@@ -187,52 +630,19 @@ fn detonar_bomba_recursive(
 ///
 /// # Ejemplo
 ///
-/// ```rust
-/// use laberinto::{guardar_laberinto_en_archivo, cargar_laberinto, Laberinto};
-///
-/// // Cargar un laberinto desde un archivo
-/// let mut laberinto = cargar_laberinto("laberinto.txt");
-///
-/// if let Ok(ref mut laberinto) = laberinto {
-///     // Realizar operaciones en el laberinto, como detonar bombas o mover personajes.
-///
-///     // Guardar el laberinto modificado en un archivo de texto
-///     if let Err(e) = guardar_laberinto_en_archivo(laberinto, "laberinto_modificado.txt") {
-///         eprintln!("Error al guardar el laberinto: {}", e);
-///     } else {
-///         println!("Laberinto guardado en {}", "laberinto_modificado.txt");
-///     }
-/// } else {
-///     println!("Error al cargar el laberinto");
-/// }
-/// ```
-///
-/// # Errores
-///
-/// Esta función retorna un `Result` que puede contener un error si no se puede abrir o escribir en
-/// el archivo especificado.
-///
-/// # Notas
-///
-/// - Si el archivo de salida no existe, se creará automáticamente.
-/// - Cada fila en el archivo de salida se termina con un salto de línea '\n'.
-///
-/// # Ejemplo
-///
-/// ```rust
-/// use laberinto::{guardar_laberinto_en_archivo, cargar_laberinto, Laberinto};
+/// ```no_run
+/// use bomberman_r::bomberman::guardar_laberinto_en_archivo;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
 ///
 /// // Cargar un laberinto desde un archivo
-/// let mut laberinto = cargar_laberinto("laberinto.txt");
+/// let laberinto = Laberinto::cargar("laberinto.txt");
 ///
-/// if let Ok(ref mut laberinto) = laberinto {
-///     // Realizar operaciones en el laberinto, como detonar bombas o mover personajes.
+/// if let Ok(laberinto) = laberinto {
+///     // Realizar operaciones en el laberinto, como detonar bombas.
 ///
-///     // Guardar el laberinto modificado en un archivo de texto
-///     if let Err(e) = guardar_laberinto_en_archivo(laberinto, "laberinto_modificado.txt") {
+///     // Guardar el laberinto modificado en `salida/laberinto.txt`
+///     if let Err(e) = guardar_laberinto_en_archivo(&laberinto, "salida", "laberinto.txt") {
 ///         eprintln!("Error al guardar el laberinto: {}", e);
-///     } else {
-///         println!("Laberinto guardado en {}", "laberinto_modificado.txt");
 ///     }
 /// } else {
 ///     println!("Error al cargar el laberinto");
@@ -248,12 +658,6 @@ fn detonar_bomba_recursive(
 ///
 /// - Si el archivo de salida no existe, se creará automáticamente.
 /// - Cada fila en el archivo de salida se termina con un salto de línea '\n'.
-///
-/// # Notas
-///
-/// - Si el archivo de salida no existe, se creará automáticamente.
-/// - Cada fila en el archivo de salida se termina con un salto de línea '\n'.
-
 pub fn guardar_laberinto_en_archivo(
     laberinto: &Laberinto,
     dir_salida: &str,
@@ -262,18 +666,10 @@ pub fn guardar_laberinto_en_archivo(
     // Obtén el nombre del archivo de entrada
     let archivo_salida = Path::new(archivo_entrada)
         .file_name()
-        .ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "No se pudo obtener el nombre del archivo de entrada",
-            )
-        })
+        .ok_or_else(|| io::Error::other("No se pudo obtener el nombre del archivo de entrada"))
         .and_then(|n| {
             n.to_str().ok_or_else(|| {
-                std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "No se pudo convertir el nombre del archivo a cadena",
-                )
+                io::Error::other("No se pudo convertir el nombre del archivo a cadena")
             })
         })?;
 
@@ -285,48 +681,8 @@ pub fn guardar_laberinto_en_archivo(
         fs::create_dir_all(dir)?;
     }
 
-    // Concatena las líneas en una cadena
-    let mut contenido = String::new();
-
-    for fila in &laberinto.grid {
-        for celda in fila {
-            match &celda.objeto {
-                Objeto::Enemigo(vidas) => {
-                    contenido.push_str(&format!("F{}", vidas));
-                }
-                Objeto::Bomba(alcance) => {
-                    contenido.push_str(&format!("B{}", alcance));
-                }
-                Objeto::BombaTraspaso(alcance) => {
-                    contenido.push_str(&format!("S{}", alcance));
-                }
-                Objeto::Roca => {
-                    contenido.push('R');
-                }
-                Objeto::Pared => {
-                    contenido.push('W');
-                }
-                Objeto::Desvio(direccion) => {
-                    let dir_str = match direccion {
-                        Direccion::Izquierda => "L",
-                        Direccion::Derecha => "R",
-                        Direccion::Arriba => "U",
-                        Direccion::Abajo => "D",
-                    };
-                    contenido.push_str(&format!("D{}", dir_str));
-                }
-                Objeto::Vacio => {
-                    contenido.push('_');
-                }
-            }
-            if celda != &fila[laberinto.tamano - 1] {
-                contenido.push(' ');
-            }
-        }
-        if fila != &laberinto.grid[laberinto.tamano - 1] {
-            contenido.push('\n'); // Nueva línea para la siguiente fila
-        }
-    }
+    // Representa el laberinto con el mismo formato del archivo de entrada
+    let contenido = laberinto.to_string();
 
     // Abre el archivo de salida para escritura, creándolo si no existe.
     let mut archivo = match File::create(&ruta_salida) {
@@ -350,22 +706,24 @@ pub fn guardar_laberinto_en_archivo(
     Ok(())
 }
 
-pub fn escribir_error_en_archivo(dir_salida: &str, archivo_entrada: &str, mensaje: &str) -> Result<(), io::Error> {
+/// Escribe un mensaje de error en el archivo de salida.
+///
+/// El archivo se crea en `dir_salida` con el mismo nombre que `archivo_entrada` y contiene una
+/// única línea de la forma `ERROR: <mensaje>`. Para errores de carga conviene pasar el
+/// [`LaberintoError`](model::error::LaberintoError) formateado, que ya indica la línea, la
+/// columna y el texto inválido.
+pub fn escribir_error_en_archivo(
+    dir_salida: &str,
+    archivo_entrada: &str,
+    mensaje: &str,
+) -> Result<(), io::Error> {
     // Obtén el nombre del archivo de entrada
     let archivo_salida = Path::new(archivo_entrada)
         .file_name()
-        .ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                "No se pudo obtener el nombre del archivo de entrada",
-            )
-        })
+        .ok_or_else(|| io::Error::other("No se pudo obtener el nombre del archivo de entrada"))
         .and_then(|n| {
             n.to_str().ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    "No se pudo convertir el nombre del archivo a cadena",
-                )
+                io::Error::other("No se pudo convertir el nombre del archivo a cadena")
             })
         })?;
 
@@ -385,16 +743,12 @@ pub fn escribir_error_en_archivo(dir_salida: &str, archivo_entrada: &str, mensaj
         }
     } else {
         eprintln!("Error al crear el archivo de error");
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "No se pudo abrir el archivo de error",
-        ));
+        return Err(io::Error::other("No se pudo abrir el archivo de error"));
     }
 
     Ok(())
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -440,4 +794,271 @@ mod tests {
             None // No debería haber dirección de desvío
         );
     }
+
+    #[test]
+    fn test_detonar_bomba_con_traza() {
+        let mut laberinto: Laberinto = "\
+            B1 F2 W\n\
+            DR _ R\n\
+            B2 _ F1"
+            .parse()
+            .unwrap();
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 2).unwrap();
+
+        assert_eq!(
+            eventos,
+            vec![
+                EventoExplosion::BombaActivada {
+                    x: 0,
+                    y: 2,
+                    profundidad: 0
+                },
+                EventoExplosion::CeldaAlcanzada { x: 1, y: 2 },
+                EventoExplosion::CeldaAlcanzada { x: 2, y: 2 },
+                EventoExplosion::EnemigoEliminado { x: 2, y: 2 },
+                EventoExplosion::Desviada {
+                    x: 0,
+                    y: 1,
+                    direccion: Direccion::Derecha
+                },
+                EventoExplosion::CeldaAlcanzada { x: 1, y: 1 },
+                EventoExplosion::Bloqueada {
+                    x: 2,
+                    y: 1,
+                    objeto: Objeto::Roca
+                },
+            ]
+        );
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+        assert_eq!(
+            eventos,
+            vec![
+                EventoExplosion::BombaActivada {
+                    x: 0,
+                    y: 0,
+                    profundidad: 0
+                },
+                EventoExplosion::CeldaAlcanzada { x: 1, y: 0 },
+                EventoExplosion::EnemigoDaniado {
+                    x: 1,
+                    y: 0,
+                    vidas: 1
+                },
+                EventoExplosion::Desviada {
+                    x: 0,
+                    y: 1,
+                    direccion: Direccion::Derecha
+                },
+                EventoExplosion::CeldaAlcanzada { x: 1, y: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_traza_bombas_encadenadas() {
+        let mut laberinto: Laberinto = "B1 S1 _ R F1".parse().unwrap();
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+        assert!(eventos.contains(&EventoExplosion::BombaActivada {
+            x: 1,
+            y: 0,
+            profundidad: 1
+        }));
+        assert_eq!(laberinto.to_string(), "_ _ _ R F1");
+    }
+
+    #[test]
+    fn test_cadena_larga_no_desborda_la_pila() {
+        let bombas = vec!["B1"; 50_000].join(" ");
+        let mut laberinto: Laberinto = bombas.parse().unwrap();
+
+        detonar_bomba(&mut laberinto, 0, 0).unwrap();
+
+        assert!(laberinto.grid[0].iter().all(|c| c.objeto == Objeto::Vacio));
+    }
+
+    #[test]
+    fn test_ciclo_de_desvios_se_detiene() {
+        let mut laberinto: Laberinto = "\
+            DR _ DD\n\
+            B9 F3 _\n\
+            DU _ DL"
+            .parse()
+            .unwrap();
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 1).unwrap();
+
+        // Hacia abajo y hacia arriba la explosión da una vuelta completa y se detiene al volver
+        // al primer desvío que atravesó
+        let ciclos: Vec<&EventoExplosion> = eventos
+            .iter()
+            .filter(|e| matches!(e, EventoExplosion::CicloDeDesvio { .. }))
+            .collect();
+        assert_eq!(
+            ciclos,
+            vec![
+                &EventoExplosion::CicloDeDesvio { x: 0, y: 2 },
+                &EventoExplosion::CicloDeDesvio { x: 0, y: 0 },
+            ]
+        );
+        // El enemigo sólo pierde una vida por la bomba
+        assert_eq!(laberinto.grid[1][1].objeto, Objeto::Enemigo(2));
+    }
+
+    #[test]
+    fn test_detonar_bombas_secuencial_y_simultaneo() {
+        let texto = "B1 F3 B2 _ B2\n_ W _ W F1\nB1 F1 _ _ _";
+        let coordenadas = [(0, 0), (2, 0), (4, 0)];
+
+        let mut secuencial: Laberinto = texto.parse().unwrap();
+        let eventos_secuencial =
+            detonar_bombas_con_traza(&mut secuencial, &coordenadas, ModoDetonacion::Secuencial)
+                .unwrap();
+
+        let mut simultaneo: Laberinto = texto.parse().unwrap();
+        let eventos_simultaneo =
+            detonar_bombas_con_traza(&mut simultaneo, &coordenadas, ModoDetonacion::Simultaneo)
+                .unwrap();
+
+        // La bomba de (2, 0) encadena la de (4, 0): explota una sola vez en ambos modos
+        assert_eq!(secuencial, simultaneo);
+        assert_eq!(secuencial.to_string(), "_ F1 _ _ _\n_ W _ W _\nB1 F1 _ _ _");
+
+        let profundidades = |eventos: &[EventoExplosion]| -> Vec<usize> {
+            eventos
+                .iter()
+                .filter_map(|e| match e {
+                    EventoExplosion::BombaActivada { profundidad, .. } => Some(*profundidad),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(profundidades(&eventos_secuencial), vec![0, 0, 1]);
+        assert_eq!(profundidades(&eventos_simultaneo), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_detonar_bombas_fuera_del_laberinto_no_modifica() {
+        let mut laberinto: Laberinto = "B1 F1".parse().unwrap();
+
+        let resultado = detonar_bombas(
+            &mut laberinto,
+            &[(0, 0), (5, 0)],
+            ModoDetonacion::Secuencial,
+        );
+
+        assert!(resultado.is_err());
+        assert_eq!(laberinto.to_string(), "B1 F1");
+    }
+
+    #[test]
+    fn test_cajas_detienen_la_explosion() {
+        // Tanto las bombas normales como las de traspaso se detienen en la caja
+        for bomba in ["B3", "S3"] {
+            let mut laberinto: Laberinto = format!("{} C2 F1", bomba).parse().unwrap();
+            let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+            assert_eq!(laberinto.to_string(), "_ C1 F1");
+            assert!(eventos.contains(&EventoExplosion::CajaDaniada {
+                x: 1,
+                y: 0,
+                resistencia: 1
+            }));
+        }
+    }
+
+    #[test]
+    fn test_caja_rota_deja_su_contenido() {
+        let mut laberinto: Laberinto = "B1 C1+F2 B1".parse().unwrap();
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+        // El contenido aparece al final, así que ninguna explosión de la cadena lo alcanza
+        assert!(eventos.contains(&EventoExplosion::CajaRota { x: 1, y: 0 }));
+        assert_eq!(laberinto.to_string(), "_ F2 B1");
+    }
+
+    #[test]
+    fn test_cajas_en_modo_secuencial_y_simultaneo() {
+        let original: Laberinto = "_ F1 _\nB1 C1 _\n_ B2 _".parse().unwrap();
+        let bombas = [(0, 1), (1, 2)];
+
+        let mut secuencial = original.clone();
+        detonar_bombas(&mut secuencial, &bombas, ModoDetonacion::Secuencial).unwrap();
+        let mut simultaneo = original.clone();
+        detonar_bombas(&mut simultaneo, &bombas, ModoDetonacion::Simultaneo).unwrap();
+
+        // En modo secuencial la caja ya está rota cuando explota la segunda bomba
+        assert_eq!(secuencial.to_string(), "_ _ _\n_ _ _\n_ _ _");
+        assert_eq!(simultaneo.to_string(), "_ F1 _\n_ _ _\n_ _ _");
+    }
+
+    #[test]
+    fn test_teletransportador_lleva_la_explosion_a_su_pareja() {
+        let mut laberinto: Laberinto = "B3 T1 _ W F1\n_ _ _ T1 F1".parse().unwrap();
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+        // Entrar en el teletransportador gasta un paso; desde la pareja queda uno más
+        assert!(eventos.contains(&EventoExplosion::Teletransportada {
+            x: 1,
+            y: 0,
+            destino_x: 3,
+            destino_y: 1
+        }));
+        assert_eq!(laberinto.to_string(), "_ T1 _ W F1\n_ _ _ T1 _");
+    }
+
+    #[test]
+    fn test_patrones_de_explosion() {
+        let alrededor = |bomba: &str| -> String {
+            let mut laberinto: Laberinto = format!("F1 F1 F1\nF1 {} F1\nF1 F1 F1", bomba)
+                .parse()
+                .unwrap();
+            detonar_bomba(&mut laberinto, 1, 1).unwrap();
+            laberinto.to_string()
+        };
+
+        assert_eq!(alrededor("B1"), "F1 _ F1\n_ _ _\nF1 _ F1");
+        assert_eq!(alrededor("B1X"), "_ F1 _\nF1 _ F1\n_ F1 _");
+        assert_eq!(alrededor("B1*"), "_ _ _\n_ _ _\n_ _ _");
+        assert_eq!(alrededor("B1#"), "_ _ _\n_ _ _\n_ _ _");
+    }
+
+    #[test]
+    fn test_patron_cuadrado_no_se_detiene() {
+        let mut laberinto: Laberinto = "F1 W F1 _\nR B1# F1 _\nF1 F1 C1 F1".parse().unwrap();
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 1, 1).unwrap();
+
+        // Las paredes y las rocas del cuadrado no sufren daño ni detienen la explosión
+        assert_eq!(laberinto.to_string(), "_ W _ _\nR _ _ _\n_ _ _ F1");
+        assert!(eventos.contains(&EventoExplosion::CajaRota { x: 2, y: 2 }));
+    }
+
+    #[test]
+    fn test_patron_cuadrado_en_el_borde() {
+        let mut laberinto: Laberinto = "S9# F1\nF2 R".parse().unwrap();
+
+        detonar_bomba(&mut laberinto, 0, 0).unwrap();
+
+        assert_eq!(laberinto.to_string(), "_ _\nF1 R");
+    }
+
+    #[test]
+    fn test_desvio_diagonal() {
+        let mut laberinto: Laberinto = "B2 DSE _\n_ _ F1".parse().unwrap();
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+        assert!(eventos.contains(&EventoExplosion::Desviada {
+            x: 1,
+            y: 0,
+            direccion: Direccion::SurEste
+        }));
+        assert_eq!(laberinto.to_string(), "_ DSE _\n_ _ _");
+    }
 }
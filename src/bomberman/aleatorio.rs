@@ -0,0 +1,88 @@
+/// Generador de números pseudoaleatorios (xorshift64).
+///
+/// No es apto para usos criptográficos, pero la misma semilla produce siempre la misma
+/// secuencia, lo que permite repetir simulaciones y pruebas de forma exacta.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::aleatorio::Generador;
+///
+/// let mut a = Generador::new(42);
+/// let mut b = Generador::new(42);
+/// assert_eq!(a.entre(100), b.entre(100));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generador {
+    estado: u64,
+}
+
+impl Generador {
+    /// Crea un generador a partir de una semilla. Cualquier semilla es válida, incluida 0.
+    pub fn new(semilla: u64) -> Self {
+        // Mezclar la semilla (splitmix64) para que semillas parecidas den secuencias distintas
+        // y el estado nunca sea 0, que xorshift no puede abandonar
+        let mut z = semilla.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Generador {
+            estado: if z == 0 { 1 } else { z },
+        }
+    }
+
+    /// Devuelve el siguiente número de la secuencia.
+    pub fn siguiente(&mut self) -> u64 {
+        self.estado ^= self.estado << 13;
+        self.estado ^= self.estado >> 7;
+        self.estado ^= self.estado << 17;
+        self.estado
+    }
+
+    /// Devuelve un número en el intervalo `[0, limite)`. `limite` debe ser mayor que 0.
+    pub fn entre(&mut self, limite: u64) -> u64 {
+        self.siguiente() % limite
+    }
+
+    /// Devuelve un número en el intervalo `[0, 1)`.
+    pub fn decimal(&mut self) -> f64 {
+        // Los 53 bits más altos alcanzan para la mantisa de un f64
+        (self.siguiente() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_misma_semilla_misma_secuencia() {
+        let mut a = Generador::new(7);
+        let mut b = Generador::new(7);
+        let mut c = Generador::new(8);
+
+        let secuencia_a: Vec<u64> = (0..10).map(|_| a.siguiente()).collect();
+        let secuencia_b: Vec<u64> = (0..10).map(|_| b.siguiente()).collect();
+        let secuencia_c: Vec<u64> = (0..10).map(|_| c.siguiente()).collect();
+
+        assert_eq!(secuencia_a, secuencia_b);
+        assert_ne!(secuencia_a, secuencia_c);
+    }
+
+    #[test]
+    fn test_semilla_cero() {
+        let mut generador = Generador::new(0);
+        assert!((0..10).any(|_| generador.siguiente() != 0));
+        assert!((0..100).all(|_| generador.entre(6) < 6));
+    }
+
+    #[test]
+    fn test_decimal() {
+        let mut generador = Generador::new(11);
+        let valores: Vec<f64> = (0..1000).map(|_| generador.decimal()).collect();
+
+        assert!(valores.iter().all(|v| (0.0..1.0).contains(v)));
+        assert!(valores.iter().any(|v| *v < 0.5));
+        assert!(valores.iter().any(|v| *v >= 0.5));
+    }
+}
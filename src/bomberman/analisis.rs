@@ -0,0 +1,346 @@
+use super::detonar_bomba;
+use super::model::evento::ResumenExplosion;
+use super::model::laberinto::Laberinto;
+use super::model::objeto::Objeto;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+/// Resultado de detonar una de las bombas del laberinto.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EvaluacionBomba {
+    /// Coordenada X de la bomba.
+    pub x: usize,
+    /// Coordenada Y de la bomba.
+    pub y: usize,
+    /// Resumen de la explosión, incluyendo las bombas encadenadas.
+    pub resumen: ResumenExplosion,
+    /// Cantidad de celdas que tenían un objeto y quedaron vacías.
+    pub celdas_despejadas: usize,
+}
+
+impl EvaluacionBomba {
+    /// Cantidad total de vidas que la explosión les quitó a los enemigos.
+    pub fn danio_total(&self) -> usize {
+        self.resumen.enemigos_eliminados + self.resumen.enemigos_daniados
+    }
+}
+
+/// Evalúa todas las bombas del laberinto, incluidas las temporizadas, las que tienen un patrón
+/// y las que tiene debajo un jugador, y las devuelve ordenadas de mejor a peor.
+///
+/// Las bombas se comparan por enemigos eliminados, luego por daño total y luego por celdas
+/// despejadas; los empates se ordenan por fila y columna. El laberinto no se modifica.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::analisis::evaluar_bombas;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
+///
+/// let laberinto: Laberinto = "B1 F1 _ B1".parse().unwrap();
+/// let ranking = evaluar_bombas(&laberinto);
+/// assert_eq!((ranking[0].x, ranking[0].y), (0, 0));
+/// ```
+pub fn evaluar_bombas(laberinto: &Laberinto) -> Vec<EvaluacionBomba> {
+    let mut evaluaciones = Vec::new();
+
+    for fila in &laberinto.grid {
+        for celda in fila {
+            if celda.objeto.como_bomba().is_none() {
+                continue;
+            }
+            // Las coordenadas salen del propio laberinto, así que la detonación no puede fallar
+            if let Ok((resultado, resumen)) = laberinto.predecir_detonacion(celda.x, celda.y) {
+                evaluaciones.push(EvaluacionBomba {
+                    x: celda.x,
+                    y: celda.y,
+                    resumen,
+                    celdas_despejadas: contar_celdas_despejadas(laberinto, &resultado),
+                });
+            }
+        }
+    }
+
+    evaluaciones.sort_by_key(|e| {
+        (
+            Reverse(e.resumen.enemigos_eliminados),
+            Reverse(e.danio_total()),
+            Reverse(e.celdas_despejadas),
+            e.y,
+            e.x,
+        )
+    });
+    evaluaciones
+}
+
+/// Devuelve la bomba cuya detonación elimina más enemigos, según el orden de
+/// [`evaluar_bombas`], o `None` si el laberinto no tiene bombas.
+pub fn mejor_bomba(laberinto: &Laberinto) -> Option<EvaluacionBomba> {
+    evaluar_bombas(laberinto).into_iter().next()
+}
+
+/// Busca la secuencia más corta de detonaciones que elimina a todos los enemigos.
+///
+/// La búsqueda es en anchura sobre los estados del laberinto, descartando los estados ya
+/// visitados. Cada paso detona una de las bombas que siguen en el laberinto; las bombas que
+/// explotan en cadena desaparecen del tablero, por lo que nunca se cuentan como detonaciones
+/// aparte.
+///
+/// Devuelve las coordenadas `(x, y)` de cada bomba en el orden en que deben detonarse (una
+/// lista vacía si no hay enemigos), o `None` si no existe ninguna secuencia que elimine a
+/// todos los enemigos.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::analisis::secuencia_minima;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
+///
+/// let laberinto: Laberinto = "B1 F2 B1".parse().unwrap();
+/// assert_eq!(secuencia_minima(&laberinto).map(|s| s.len()), Some(2));
+/// ```
+pub fn secuencia_minima(laberinto: &Laberinto) -> Option<Vec<(usize, usize)>> {
+    let mut visitados = HashSet::new();
+    let mut pendientes = VecDeque::new();
+    visitados.insert(estado(laberinto));
+    pendientes.push_back((laberinto.clone(), Vec::new()));
+
+    while let Some((actual, secuencia)) = pendientes.pop_front() {
+        if !tiene_enemigos(&actual) {
+            return Some(secuencia);
+        }
+
+        for (x, y) in posiciones_de_bombas(&actual) {
+            let mut siguiente = actual.clone();
+            if detonar_bomba(&mut siguiente, x, y).is_err() {
+                continue;
+            }
+            if visitados.insert(estado(&siguiente)) {
+                let mut nueva_secuencia = secuencia.clone();
+                nueva_secuencia.push((x, y));
+                pendientes.push_back((siguiente, nueva_secuencia));
+            }
+        }
+    }
+
+    None
+}
+
+/// Busca todas las formas de eliminar a todos los enemigos con, como mucho, `maximo`
+/// detonaciones.
+///
+/// Cada solución es el conjunto de bombas que hay que detonar, con sus coordenadas `(x, y)`
+/// ordenadas por fila y columna: detonar las mismas bombas en otro orden no cuenta como otra
+/// solución. Las soluciones se devuelven de menos a más detonaciones. Si el laberinto no tiene
+/// enemigos, la única solución es no detonar nada.
+///
+/// La búsqueda es en profundidad y recuerda los estados ya visitados junto con las bombas
+/// detonadas para llegar a ellos, así que cada conjunto de detonaciones se explora una sola
+/// vez aunque se pueda detonar en distintos órdenes.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::analisis::soluciones;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
+///
+/// let laberinto: Laberinto = "B1 F1 B1".parse().unwrap();
+/// assert_eq!(soluciones(&laberinto, 1), vec![vec![(0, 0)], vec![(2, 0)]]);
+/// ```
+pub fn soluciones(laberinto: &Laberinto, maximo: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut encontradas = BTreeSet::new();
+    buscar_soluciones(
+        laberinto,
+        maximo,
+        &mut Vec::new(),
+        &mut HashMap::new(),
+        &mut encontradas,
+    );
+    let mut soluciones: Vec<_> = encontradas.into_iter().collect();
+    soluciones.sort_by_key(|solucion| {
+        let filas: Vec<_> = solucion.iter().map(|&(x, y)| (y, x)).collect();
+        (solucion.len(), filas)
+    });
+    soluciones
+}
+
+// Estado del laberinto y bombas detonadas, ordenadas, con las detonaciones restantes
+type Visitados = HashMap<(Vec<Objeto>, Vec<(usize, usize)>), usize>;
+
+// Búsqueda en profundidad que agrega a `encontradas` las soluciones que empiezan con `secuencia`.
+// `visitados` guarda, para cada estado y conjunto de bombas detonadas, la mayor cantidad de
+// detonaciones restantes con la que ya se exploró: con las mismas o menos no aparece ninguna
+// solución nueva.
+fn buscar_soluciones(
+    laberinto: &Laberinto,
+    restantes: usize,
+    secuencia: &mut Vec<(usize, usize)>,
+    visitados: &mut Visitados,
+    encontradas: &mut BTreeSet<Vec<(usize, usize)>>,
+) {
+    let mut detonadas = secuencia.clone();
+    detonadas.sort_by_key(|&(x, y)| (y, x));
+    if !tiene_enemigos(laberinto) {
+        encontradas.insert(detonadas);
+        return;
+    }
+    if restantes == 0 {
+        return;
+    }
+    let explorado = visitados.entry((estado(laberinto), detonadas)).or_insert(0);
+    if *explorado >= restantes {
+        return;
+    }
+    *explorado = restantes;
+
+    for (x, y) in posiciones_de_bombas(laberinto) {
+        let mut siguiente = laberinto.clone();
+        if detonar_bomba(&mut siguiente, x, y).is_err() {
+            continue;
+        }
+        secuencia.push((x, y));
+        buscar_soluciones(&siguiente, restantes - 1, secuencia, visitados, encontradas);
+        secuencia.pop();
+    }
+}
+
+// Objetos del laberinto en orden, que identifican un estado de la búsqueda
+fn estado(laberinto: &Laberinto) -> Vec<Objeto> {
+    laberinto
+        .grid
+        .iter()
+        .flatten()
+        .map(|celda| celda.objeto.clone())
+        .collect()
+}
+
+fn tiene_enemigos(laberinto: &Laberinto) -> bool {
+    laberinto
+        .grid
+        .iter()
+        .flatten()
+        .any(|celda| matches!(celda.objeto, Objeto::Enemigo(_)))
+}
+
+// Posiciones de todas las bombas, igual que en `evaluar_bombas`
+fn posiciones_de_bombas(laberinto: &Laberinto) -> Vec<(usize, usize)> {
+    laberinto
+        .grid
+        .iter()
+        .flatten()
+        .filter(|celda| celda.objeto.como_bomba().is_some())
+        .map(|celda| (celda.x, celda.y))
+        .collect()
+}
+
+fn contar_celdas_despejadas(antes: &Laberinto, despues: &Laberinto) -> usize {
+    antes
+        .grid
+        .iter()
+        .flatten()
+        .zip(despues.grid.iter().flatten())
+        .filter(|(a, d)| a.objeto != Objeto::Vacio && d.objeto == Objeto::Vacio)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluar_bombas_ordena_por_criterios() {
+        let laberinto: Laberinto = "\
+            B1 F1 _ _ B2\n\
+            _ _ _ _ F2\n\
+            B1 F2 _ _ F1"
+            .parse()
+            .unwrap();
+
+        let ranking = evaluar_bombas(&laberinto);
+        let posiciones: Vec<(usize, usize)> = ranking.iter().map(|e| (e.x, e.y)).collect();
+
+        // (4, 0) elimina un enemigo y daña otro; (0, 0) sólo elimina uno; (0, 2) sólo daña
+        assert_eq!(posiciones, vec![(4, 0), (0, 0), (0, 2)]);
+        assert_eq!(ranking[0].danio_total(), 2);
+        assert_eq!(ranking[0].celdas_despejadas, 2);
+        assert_eq!(ranking[2].resumen.enemigos_eliminados, 0);
+    }
+
+    #[test]
+    fn test_evaluar_bombas_incluye_todas_las_bombas() {
+        let laberinto: Laberinto = "B1:3 F1 _ B1X _ S1#\n_ _ P1+B1 _ _ _".parse().unwrap();
+
+        let mut posiciones: Vec<(usize, usize)> = evaluar_bombas(&laberinto)
+            .iter()
+            .map(|e| (e.x, e.y))
+            .collect();
+        posiciones.sort();
+
+        assert_eq!(posiciones, vec![(0, 0), (2, 1), (3, 0), (5, 0)]);
+        assert_eq!(posiciones_de_bombas(&laberinto).len(), 4);
+    }
+
+    #[test]
+    fn test_secuencia_minima() {
+        // La bomba de (0, 0) encadena la de (2, 0), así que alcanza con una detonación
+        let laberinto: Laberinto = "\
+            B2 _ B1 F1\n\
+            _ W _ W\n\
+            F1 _ _ _"
+            .parse()
+            .unwrap();
+        assert_eq!(secuencia_minima(&laberinto), Some(vec![(0, 0)]));
+
+        // El enemigo de dos vidas necesita dos explosiones separadas
+        let laberinto: Laberinto = "B1 F2 B1 F1\n_ W _ W".parse().unwrap();
+        let secuencia = secuencia_minima(&laberinto).unwrap();
+        assert_eq!(secuencia.len(), 2);
+        let mut resultado = laberinto.clone();
+        for (x, y) in secuencia {
+            detonar_bomba(&mut resultado, x, y).unwrap();
+        }
+        assert!(!tiene_enemigos(&resultado));
+    }
+
+    #[test]
+    fn test_secuencia_minima_sin_solucion() {
+        let laberinto: Laberinto = "B1 W F1\n_ _ _".parse().unwrap();
+        assert_eq!(secuencia_minima(&laberinto), None);
+
+        let laberinto: Laberinto = "B1 _ W".parse().unwrap();
+        assert_eq!(secuencia_minima(&laberinto), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_soluciones() {
+        // La bomba de la izquierda sólo alcanza al primer enemigo; la otra, a los dos
+        let laberinto: Laberinto = "B1 F1 _ B2 F1".parse().unwrap();
+
+        assert_eq!(soluciones(&laberinto, 1), vec![vec![(3, 0)]]);
+        assert_eq!(
+            soluciones(&laberinto, 2),
+            vec![vec![(3, 0)], vec![(0, 0), (3, 0)]]
+        );
+        assert_eq!(soluciones(&"_ B1".parse().unwrap(), 1), vec![vec![]]);
+        assert!(soluciones(&"F1 W B1".parse().unwrap(), 3).is_empty());
+    }
+
+    #[test]
+    fn test_soluciones_que_llegan_al_mismo_estado() {
+        // Detonar cualquiera de las dos primeras bombas hace explotar la otra, así que ambas
+        // dejan el mismo laberinto pero son soluciones distintas
+        let laberinto: Laberinto = "F1 B1 B1 _ B1 F1".parse().unwrap();
+
+        assert!(soluciones(&laberinto, 1).is_empty());
+        assert_eq!(
+            soluciones(&laberinto, 2),
+            vec![vec![(1, 0), (4, 0)], vec![(2, 0), (4, 0)]]
+        );
+    }
+
+    #[test]
+    fn test_mejor_bomba_sin_bombas() {
+        let laberinto: Laberinto = "_ F1\nW _".parse().unwrap();
+        assert_eq!(mejor_bomba(&laberinto), None);
+    }
+}
@@ -0,0 +1,227 @@
+use super::aleatorio::Generador;
+use super::model::direccion::Direccion;
+use super::model::laberinto::Laberinto;
+use super::model::objeto::Objeto;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Comportamiento de un enemigo que se mueve en cada turno de una
+/// [`Simulacion`](super::simulacion::Simulacion).
+///
+/// Los enemigos sólo pueden moverse a una celda vacía: las paredes, las rocas, las bombas, los
+/// desvíos, los jugadores y los otros enemigos los bloquean. La simulación verifica el
+/// movimiento elegido y, si no es posible, deja al enemigo en su lugar.
+pub trait ComportamientoEnemigo: fmt::Debug {
+    /// Elige la dirección en la que se mueve el enemigo que está en `(x, y)`, o `None` si se
+    /// queda quieto.
+    fn elegir_movimiento(&mut self, laberinto: &Laberinto, x: usize, y: usize)
+        -> Option<Direccion>;
+}
+
+/// Devuelve la celda a la que llega un enemigo que se mueve desde `(x, y)` en `direccion`, si
+/// está dentro del laberinto y vacía.
+pub fn destino_libre(
+    laberinto: &Laberinto,
+    x: usize,
+    y: usize,
+    direccion: &Direccion,
+) -> Option<(usize, usize)> {
+    let (dx, dy) = direccion.desplazamiento();
+    let nuevo_x = x.wrapping_add(dx as usize);
+    let nuevo_y = y.wrapping_add(dy as usize);
+    if nuevo_x >= laberinto.ancho || nuevo_y >= laberinto.alto {
+        return None;
+    }
+    (laberinto.grid[nuevo_y][nuevo_x].objeto == Objeto::Vacio).then_some((nuevo_x, nuevo_y))
+}
+
+/// Camina al azar: en cada turno elige una de las direcciones libres con la misma
+/// probabilidad. La misma semilla produce siempre los mismos movimientos.
+#[derive(Debug, Clone)]
+pub struct CaminataAleatoria {
+    generador: Generador,
+}
+
+impl CaminataAleatoria {
+    /// Crea el comportamiento a partir de una semilla.
+    pub fn new(semilla: u64) -> Self {
+        CaminataAleatoria {
+            generador: Generador::new(semilla),
+        }
+    }
+}
+
+impl ComportamientoEnemigo for CaminataAleatoria {
+    fn elegir_movimiento(
+        &mut self,
+        laberinto: &Laberinto,
+        x: usize,
+        y: usize,
+    ) -> Option<Direccion> {
+        let libres: Vec<&Direccion> = Direccion::CARDINALES
+            .iter()
+            .filter(|direccion| destino_libre(laberinto, x, y, direccion).is_some())
+            .collect();
+        if libres.is_empty() {
+            return None;
+        }
+        let elegida = self.generador.entre(libres.len() as u64) as usize;
+        Some(libres[elegida].clone())
+    }
+}
+
+/// Persigue al jugador más cercano, avanzando por el camino más corto de celdas vacías hasta
+/// quedar al lado de él. Si ningún jugador es alcanzable, se queda quieto.
+#[derive(Debug, Clone, Default)]
+pub struct PersecucionJugador;
+
+impl ComportamientoEnemigo for PersecucionJugador {
+    fn elegir_movimiento(
+        &mut self,
+        laberinto: &Laberinto,
+        x: usize,
+        y: usize,
+    ) -> Option<Direccion> {
+        // Búsqueda en anchura que recuerda la dirección del primer paso de cada camino
+        let mut visitadas = vec![vec![false; laberinto.ancho]; laberinto.alto];
+        visitadas[y][x] = true;
+        let mut cola = VecDeque::from([(x, y, None::<&Direccion>)]);
+
+        while let Some((actual_x, actual_y, primer_paso)) = cola.pop_front() {
+            for direccion in Direccion::CARDINALES.iter() {
+                let (dx, dy) = direccion.desplazamiento();
+                let vecino_x = actual_x.wrapping_add(dx as usize);
+                let vecino_y = actual_y.wrapping_add(dy as usize);
+                if vecino_x >= laberinto.ancho
+                    || vecino_y >= laberinto.alto
+                    || visitadas[vecino_y][vecino_x]
+                {
+                    continue;
+                }
+                visitadas[vecino_y][vecino_x] = true;
+
+                match laberinto.grid[vecino_y][vecino_x].objeto {
+                    // Si el jugador ya está al lado, no hace falta moverse
+                    Objeto::Jugador(..) => return primer_paso.cloned(),
+                    Objeto::Vacio => {
+                        cola.push_back((vecino_x, vecino_y, primer_paso.or(Some(direccion))))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Patrulla una fila o una columna: avanza en una dirección hasta encontrar un obstáculo y
+/// entonces da la vuelta.
+#[derive(Debug, Clone)]
+pub struct Patrulla {
+    direccion: Direccion,
+}
+
+impl Patrulla {
+    /// Crea una patrulla que empieza avanzando en `direccion`. Con `Izquierda` o `Derecha`
+    /// recorre una fila, y con `Arriba` o `Abajo`, una columna.
+    pub fn new(direccion: Direccion) -> Self {
+        Patrulla { direccion }
+    }
+}
+
+impl ComportamientoEnemigo for Patrulla {
+    fn elegir_movimiento(
+        &mut self,
+        laberinto: &Laberinto,
+        x: usize,
+        y: usize,
+    ) -> Option<Direccion> {
+        if destino_libre(laberinto, x, y, &self.direccion).is_none() {
+            self.direccion = self.direccion.opuesta();
+        }
+        destino_libre(laberinto, x, y, &self.direccion).map(|_| self.direccion.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desde_texto(texto: &str) -> Laberinto {
+        texto.parse().unwrap()
+    }
+
+    #[test]
+    fn test_destino_libre() {
+        let laberinto = desde_texto("_ F1 W\nB1 R _");
+
+        assert_eq!(
+            destino_libre(&laberinto, 1, 0, &Direccion::Izquierda),
+            Some((0, 0))
+        );
+        assert_eq!(destino_libre(&laberinto, 1, 0, &Direccion::Derecha), None);
+        assert_eq!(destino_libre(&laberinto, 1, 0, &Direccion::Abajo), None);
+        assert_eq!(destino_libre(&laberinto, 1, 0, &Direccion::Arriba), None);
+        assert_eq!(destino_libre(&laberinto, 0, 0, &Direccion::Abajo), None);
+    }
+
+    #[test]
+    fn test_caminata_aleatoria_respeta_obstaculos() {
+        let laberinto = desde_texto("W _ W\nR F1 B1\nW W W");
+        let mut caminata = CaminataAleatoria::new(3);
+
+        for _ in 0..20 {
+            assert_eq!(
+                caminata.elegir_movimiento(&laberinto, 1, 1),
+                Some(Direccion::Arriba)
+            );
+        }
+
+        let encerrado = desde_texto("W W W\nR F1 B1\nW W W");
+        assert_eq!(caminata.elegir_movimiento(&encerrado, 1, 1), None);
+    }
+
+    #[test]
+    fn test_persecucion_toma_el_camino_mas_corto() {
+        let laberinto = desde_texto("F1 W _ _\n_ W _ W\n_ _ _ P1");
+        let mut persecucion = PersecucionJugador;
+
+        assert_eq!(
+            persecucion.elegir_movimiento(&laberinto, 0, 0),
+            Some(Direccion::Abajo)
+        );
+
+        // Al lado del jugador se queda quieto
+        let laberinto = desde_texto("F1 P1");
+        assert_eq!(persecucion.elegir_movimiento(&laberinto, 0, 0), None);
+
+        // Sin camino hacia ningún jugador también
+        let laberinto = desde_texto("F1 W P1");
+        assert_eq!(persecucion.elegir_movimiento(&laberinto, 0, 0), None);
+    }
+
+    #[test]
+    fn test_persecucion_elige_el_jugador_mas_cercano() {
+        let laberinto = desde_texto("P1 _ _ F1 _ P2");
+        let mut persecucion = PersecucionJugador;
+
+        assert_eq!(
+            persecucion.elegir_movimiento(&laberinto, 3, 0),
+            Some(Direccion::Derecha)
+        );
+    }
+
+    #[test]
+    fn test_patrulla_da_la_vuelta() {
+        let laberinto = desde_texto("W F1 _ W");
+        let mut patrulla = Patrulla::new(Direccion::Izquierda);
+
+        assert_eq!(
+            patrulla.elegir_movimiento(&laberinto, 1, 0),
+            Some(Direccion::Derecha)
+        );
+
+        let encerrado = desde_texto("W F1 W");
+        assert_eq!(patrulla.elegir_movimiento(&encerrado, 1, 0), None);
+    }
+}
@@ -0,0 +1,698 @@
+use super::aleatorio::Generador;
+use super::analisis::soluciones;
+use super::model::celda::Celda;
+use super::model::direccion::Direccion;
+use super::model::error::GeneracionError;
+use super::model::evento::EventoExplosion;
+use super::model::laberinto::Laberinto;
+use super::model::objeto::Objeto;
+use super::{detonar_bomba, detonar_bomba_con_traza};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+/// Cómo se ubican las paredes en un laberinto generado.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisposicionParedes {
+    /// Una pared en cada celda de columna y fila impares, como en los laberintos de ejemplo.
+    Pilares,
+    /// Cada celda es una pared con la probabilidad indicada.
+    Aleatoria(f64),
+}
+
+/// Parámetros para generar un laberinto con [`generar`].
+///
+/// Las densidades son la probabilidad, entre 0 y 1, de que una celda sin pared contenga ese
+/// objeto; su suma no puede superar 1.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::generador::{generar, ParametrosGeneracion};
+///
+/// let parametros = ParametrosGeneracion {
+///     ancho: 9,
+///     alto: 5,
+///     enemigos: 0.2,
+///     ..Default::default()
+/// };
+/// let laberinto = generar(&parametros, 42).unwrap();
+/// assert_eq!((laberinto.ancho, laberinto.alto), (9, 5));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParametrosGeneracion {
+    /// Cantidad de columnas.
+    pub ancho: usize,
+    /// Cantidad de filas.
+    pub alto: usize,
+    /// Disposición de las paredes.
+    pub paredes: DisposicionParedes,
+    /// Densidad de rocas.
+    pub rocas: f64,
+    /// Densidad de enemigos.
+    pub enemigos: f64,
+    /// Densidad de bombas normales.
+    pub bombas: f64,
+    /// Densidad de bombas de traspaso.
+    pub bombas_traspaso: f64,
+    /// Densidad de desvíos.
+    pub desvios: f64,
+    /// Vidas máximas de un enemigo; cada uno tiene entre 1 y este valor.
+    pub vidas_maximas: i32,
+    /// Alcance máximo de una bomba; cada una tiene entre 1 y este valor.
+    pub alcance_maximo: i32,
+}
+
+impl Default for ParametrosGeneracion {
+    fn default() -> Self {
+        ParametrosGeneracion {
+            ancho: 7,
+            alto: 7,
+            paredes: DisposicionParedes::Pilares,
+            rocas: 0.15,
+            enemigos: 0.1,
+            bombas: 0.08,
+            bombas_traspaso: 0.03,
+            desvios: 0.04,
+            vidas_maximas: 3,
+            alcance_maximo: 3,
+        }
+    }
+}
+
+impl ParametrosGeneracion {
+    // Densidades de los objetos en el orden en que se sortean
+    fn densidades(&self) -> [(&'static str, f64); 5] {
+        [
+            ("rocas", self.rocas),
+            ("enemigos", self.enemigos),
+            ("bombas", self.bombas),
+            ("bombas de traspaso", self.bombas_traspaso),
+            ("desvíos", self.desvios),
+        ]
+    }
+
+    fn validar(&self) -> Result<(), GeneracionError> {
+        if self.ancho == 0 || self.alto == 0 {
+            return Err(GeneracionError::TamanoInvalido {
+                ancho: self.ancho,
+                alto: self.alto,
+            });
+        }
+
+        let mut densidades = self.densidades().to_vec();
+        if let DisposicionParedes::Aleatoria(densidad) = self.paredes {
+            densidades.push(("paredes", densidad));
+        }
+        for (nombre, valor) in densidades {
+            if !(0.0..=1.0).contains(&valor) {
+                return Err(GeneracionError::DensidadInvalida { nombre, valor });
+            }
+        }
+
+        // Tolerar el redondeo de sumas como 0.1 + 0.2 + 0.7
+        let total: f64 = self.densidades().iter().map(|(_, valor)| valor).sum();
+        if total > 1.0 + 1e-9 {
+            return Err(GeneracionError::DensidadesExcesivas(total));
+        }
+        Ok(())
+    }
+}
+
+/// Genera un laberinto a partir de `parametros` y una `semilla`.
+///
+/// La misma semilla con los mismos parámetros produce siempre el mismo laberinto. Las vidas de
+/// los enemigos y los alcances de las bombas son al menos 1, aunque los máximos indicados sean
+/// menores, y los desvíos apuntan sólo en las direcciones cardinales.
+///
+/// # Errores
+///
+/// Devuelve un [`GeneracionError`] si el tamaño es 0 o alguna densidad no es válida.
+pub fn generar(
+    parametros: &ParametrosGeneracion,
+    semilla: u64,
+) -> Result<Laberinto, GeneracionError> {
+    parametros.validar()?;
+    let mut generador = Generador::new(semilla);
+
+    let grid = (0..parametros.alto)
+        .map(|y| {
+            (0..parametros.ancho)
+                .map(|x| Celda {
+                    objeto: generar_objeto(parametros, &mut generador, x, y),
+                    x,
+                    y,
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(Laberinto {
+        ancho: parametros.ancho,
+        alto: parametros.alto,
+        grid,
+    })
+}
+
+fn generar_objeto(
+    parametros: &ParametrosGeneracion,
+    generador: &mut Generador,
+    x: usize,
+    y: usize,
+) -> Objeto {
+    let es_pared = match parametros.paredes {
+        DisposicionParedes::Pilares => x % 2 == 1 && y % 2 == 1,
+        DisposicionParedes::Aleatoria(densidad) => generador.decimal() < densidad,
+    };
+    if es_pared {
+        return Objeto::Pared;
+    }
+
+    // Un único sorteo por celda: cada objeto ocupa un tramo de [0, 1) tan largo como su
+    // densidad, y lo que sobra queda vacío
+    let mut sorteo = generador.decimal();
+    let mut elegido = None;
+    for (indice, (_, densidad)) in parametros.densidades().iter().enumerate() {
+        if sorteo < *densidad {
+            elegido = Some(indice);
+            break;
+        }
+        sorteo -= densidad;
+    }
+
+    match elegido {
+        Some(0) => Objeto::Roca,
+        Some(1) => Objeto::Enemigo(entre_uno_y(generador, parametros.vidas_maximas)),
+        Some(2) => Objeto::Bomba(entre_uno_y(generador, parametros.alcance_maximo)),
+        Some(3) => Objeto::BombaTraspaso(entre_uno_y(generador, parametros.alcance_maximo)),
+        Some(_) => {
+            let indice = generador.entre(Direccion::CARDINALES.len() as u64) as usize;
+            Objeto::Desvio(Direccion::CARDINALES[indice].clone())
+        }
+        None => Objeto::Vacio,
+    }
+}
+
+// Número entre 1 y `maximo` (o 1 si `maximo` es menor)
+fn entre_uno_y(generador: &mut Generador, maximo: i32) -> i32 {
+    1 + generador.entre(maximo.max(1) as u64) as i32
+}
+
+/// Parámetros para generar un puzzle con [`generar_puzzle`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParametrosPuzzle {
+    /// Parámetros del laberinto de base. Su densidad de enemigos se ignora: los enemigos se
+    /// ubican en las celdas que alcanza la solución.
+    pub laberinto: ParametrosGeneracion,
+    /// Cantidad de bombas que hay que detonar para resolver el puzzle.
+    pub detonaciones: usize,
+    /// Cantidad de enemigos. Siempre hay al menos uno por detonación, y nunca más que las
+    /// celdas vacías que alcanza la solución.
+    pub enemigos: usize,
+    /// Si es `true`, ninguna otra combinación de hasta `detonaciones` bombas elimina a todos
+    /// los enemigos.
+    pub solucion_unica: bool,
+    /// Cantidad de laberintos que se prueban antes de darse por vencido.
+    pub intentos: usize,
+}
+
+impl Default for ParametrosPuzzle {
+    fn default() -> Self {
+        ParametrosPuzzle {
+            laberinto: ParametrosGeneracion {
+                enemigos: 0.0,
+                bombas: 0.12,
+                ..Default::default()
+            },
+            detonaciones: 1,
+            enemigos: 3,
+            solucion_unica: true,
+            intentos: 500,
+        }
+    }
+}
+
+/// Qué tan difícil es encontrar la solución de un puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dificultad {
+    /// Cantidad de bombas que hay que detonar.
+    pub detonaciones: usize,
+    /// Mayor profundidad de las cadenas de bombas de la solución.
+    pub profundidad_cadena: usize,
+    /// Cantidad de bombas que explotan encadenadas.
+    pub bombas_encadenadas: usize,
+    /// Cantidad de veces que la solución atraviesa un desvío.
+    pub desvios: usize,
+}
+
+impl Dificultad {
+    /// Puntaje total: la suma de las detonaciones, la profundidad de las cadenas y los desvíos
+    /// atravesados.
+    pub fn puntaje(&self) -> usize {
+        self.detonaciones + self.profundidad_cadena + self.desvios
+    }
+}
+
+/// Un laberinto cuyos enemigos se eliminan detonando las bombas de `solucion` en orden.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Puzzle {
+    /// Laberinto del puzzle.
+    pub laberinto: Laberinto,
+    /// Coordenadas `(x, y)` de las bombas a detonar, en orden.
+    pub solucion: Vec<(usize, usize)>,
+    /// Dificultad de la solución.
+    pub dificultad: Dificultad,
+}
+
+impl Puzzle {
+    /// Escribe la solución y la dificultad, una por línea, en `escritor`:
+    ///
+    /// ```text
+    /// solucion: (4, 0) (2, 2)
+    /// detonaciones: 2
+    /// profundidad: 1
+    /// encadenadas: 1
+    /// desvios: 0
+    /// dificultad: 3
+    /// ```
+    pub fn escribir_solucion(&self, mut escritor: impl Write) -> io::Result<()> {
+        write!(escritor, "solucion:")?;
+        for (x, y) in &self.solucion {
+            write!(escritor, " ({}, {})", x, y)?;
+        }
+        writeln!(escritor)?;
+        writeln!(escritor, "detonaciones: {}", self.dificultad.detonaciones)?;
+        writeln!(
+            escritor,
+            "profundidad: {}",
+            self.dificultad.profundidad_cadena
+        )?;
+        writeln!(
+            escritor,
+            "encadenadas: {}",
+            self.dificultad.bombas_encadenadas
+        )?;
+        writeln!(escritor, "desvios: {}", self.dificultad.desvios)?;
+        writeln!(escritor, "dificultad: {}", self.dificultad.puntaje())
+    }
+}
+
+/// Genera un puzzle: un laberinto en el que detonar unas bombas determinadas elimina a todos
+/// los enemigos.
+///
+/// Se genera un laberinto sin enemigos, se eligen al azar las bombas de la solución y se
+/// ubican los enemigos en celdas vacías que alcanzan sus explosiones, con al menos uno que
+/// sólo alcanza cada detonación. El resultado se verifica con [`detonar_bomba`] y, si se pide
+/// una solución única, con [`soluciones`]; si no cumple, se prueba con otro laberinto.
+///
+/// # Errores
+///
+/// Devuelve un [`GeneracionError`] si los parámetros no son válidos o si ninguno de los
+/// intentos produjo un puzzle.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::detonar_bomba;
+/// use bomberman_r::bomberman::generador::{generar_puzzle, ParametrosPuzzle};
+///
+/// let puzzle = generar_puzzle(&ParametrosPuzzle::default(), 7).unwrap();
+/// let mut laberinto = puzzle.laberinto.clone();
+/// for (x, y) in &puzzle.solucion {
+///     detonar_bomba(&mut laberinto, *x, *y).unwrap();
+/// }
+/// assert!(!laberinto.to_string().contains('F'));
+/// ```
+pub fn generar_puzzle(
+    parametros: &ParametrosPuzzle,
+    semilla: u64,
+) -> Result<Puzzle, GeneracionError> {
+    if parametros.detonaciones == 0 {
+        return Err(GeneracionError::SinDetonaciones);
+    }
+    let base = ParametrosGeneracion {
+        enemigos: 0.0,
+        ..parametros.laberinto.clone()
+    };
+
+    let mut generador = Generador::new(semilla);
+    for _ in 0..parametros.intentos {
+        let laberinto = generar(&base, generador.siguiente())?;
+        if let Some(puzzle) = intentar_puzzle(parametros, laberinto, &mut generador) {
+            return Ok(puzzle);
+        }
+    }
+    Err(GeneracionError::PuzzleNoEncontrado {
+        intentos: parametros.intentos,
+    })
+}
+
+// Intenta convertir `laberinto`, que no tiene enemigos, en un puzzle
+fn intentar_puzzle(
+    parametros: &ParametrosPuzzle,
+    laberinto: Laberinto,
+    generador: &mut Generador,
+) -> Option<Puzzle> {
+    let mut dificultad = Dificultad {
+        detonaciones: parametros.detonaciones,
+        ..Default::default()
+    };
+
+    // Elegir cada bomba de la solución entre las que quedan después de las anteriores y
+    // guardar las celdas vacías que alcanza
+    let mut tablero = laberinto.clone();
+    let mut solucion = Vec::new();
+    let mut alcanzadas = Vec::new();
+    for _ in 0..parametros.detonaciones {
+        let bombas: Vec<(usize, usize)> = tablero
+            .grid
+            .iter()
+            .flatten()
+            .filter(|celda| celda.objeto.como_bomba().is_some())
+            .map(|celda| (celda.x, celda.y))
+            .collect();
+        if bombas.is_empty() {
+            return None;
+        }
+        let (x, y) = bombas[generador.entre(bombas.len() as u64) as usize];
+
+        let mut celdas = BTreeSet::new();
+        for evento in detonar_bomba_con_traza(&mut tablero, x, y).ok()? {
+            match evento {
+                EventoExplosion::CeldaAlcanzada { x, y }
+                    if laberinto.grid[y][x].objeto == Objeto::Vacio =>
+                {
+                    celdas.insert((x, y));
+                }
+                EventoExplosion::BombaActivada { profundidad, .. } if profundidad > 0 => {
+                    dificultad.bombas_encadenadas += 1;
+                    dificultad.profundidad_cadena = dificultad.profundidad_cadena.max(profundidad);
+                }
+                EventoExplosion::Desviada { .. } => dificultad.desvios += 1,
+                _ => {}
+            }
+        }
+        solucion.push((x, y));
+        alcanzadas.push(celdas);
+    }
+
+    // Un enemigo que sólo alcanza cada detonación, para que ninguna sobre
+    let mut puzzle = laberinto;
+    for (indice, celdas) in alcanzadas.iter().enumerate() {
+        let exclusivas: Vec<&(usize, usize)> = celdas
+            .iter()
+            .filter(|celda| {
+                alcanzadas
+                    .iter()
+                    .enumerate()
+                    .all(|(otro, otras)| otro == indice || !otras.contains(celda))
+            })
+            .collect();
+        if exclusivas.is_empty() {
+            return None;
+        }
+        let &(x, y) = exclusivas[generador.entre(exclusivas.len() as u64) as usize];
+        puzzle.grid[y][x].objeto = Objeto::Enemigo(1);
+    }
+
+    // El resto de los enemigos, en cualquier celda vacía alcanzada
+    let mut libres: Vec<(usize, usize)> = alcanzadas
+        .iter()
+        .flatten()
+        .filter(|&&(x, y)| puzzle.grid[y][x].objeto == Objeto::Vacio)
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    for _ in parametros.detonaciones..parametros.enemigos {
+        if libres.is_empty() {
+            break;
+        }
+        let (x, y) = libres.swap_remove(generador.entre(libres.len() as u64) as usize);
+        puzzle.grid[y][x].objeto = Objeto::Enemigo(1);
+    }
+
+    // Verificar la solución con el motor de explosiones
+    let mut verificacion = puzzle.clone();
+    for &(x, y) in &solucion {
+        detonar_bomba(&mut verificacion, x, y).ok()?;
+    }
+    if verificacion
+        .grid
+        .iter()
+        .flatten()
+        .any(|celda| matches!(celda.objeto, Objeto::Enemigo(_)))
+    {
+        return None;
+    }
+
+    if parametros.solucion_unica {
+        let mut esperada = solucion.clone();
+        esperada.sort_by_key(|&(x, y)| (y, x));
+        if soluciones(&puzzle, parametros.detonaciones) != [esperada] {
+            return None;
+        }
+    }
+
+    Some(Puzzle {
+        laberinto: puzzle,
+        solucion,
+        dificultad,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_misma_semilla_mismo_laberinto() {
+        let parametros = ParametrosGeneracion::default();
+
+        let a = generar(&parametros, 5).unwrap();
+        let b = generar(&parametros, 5).unwrap();
+        let c = generar(&parametros, 6).unwrap();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_pilares_como_los_ejemplos() {
+        let parametros = ParametrosGeneracion {
+            ancho: 7,
+            alto: 5,
+            ..Default::default()
+        };
+        let laberinto = generar(&parametros, 1).unwrap();
+
+        assert_eq!((laberinto.ancho, laberinto.alto), (7, 5));
+        for fila in &laberinto.grid {
+            for celda in fila {
+                let pilar = celda.x % 2 == 1 && celda.y % 2 == 1;
+                assert_eq!(celda.objeto == Objeto::Pared, pilar);
+            }
+        }
+    }
+
+    #[test]
+    fn test_densidades_extremas() {
+        let solo_enemigos = ParametrosGeneracion {
+            paredes: DisposicionParedes::Aleatoria(0.0),
+            rocas: 0.0,
+            enemigos: 1.0,
+            bombas: 0.0,
+            bombas_traspaso: 0.0,
+            desvios: 0.0,
+            vidas_maximas: 2,
+            ..Default::default()
+        };
+        let laberinto = generar(&solo_enemigos, 3).unwrap();
+        assert!(laberinto
+            .grid
+            .iter()
+            .flatten()
+            .all(|celda| matches!(celda.objeto, Objeto::Enemigo(1..=2))));
+
+        let solo_paredes = ParametrosGeneracion {
+            paredes: DisposicionParedes::Aleatoria(1.0),
+            ..Default::default()
+        };
+        let laberinto = generar(&solo_paredes, 3).unwrap();
+        assert!(laberinto
+            .grid
+            .iter()
+            .flatten()
+            .all(|celda| celda.objeto == Objeto::Pared));
+    }
+
+    #[test]
+    fn test_el_laberinto_generado_se_puede_cargar() {
+        let parametros = ParametrosGeneracion {
+            ancho: 11,
+            alto: 9,
+            paredes: DisposicionParedes::Aleatoria(0.2),
+            rocas: 0.1,
+            enemigos: 0.2,
+            bombas: 0.2,
+            bombas_traspaso: 0.1,
+            desvios: 0.2,
+            ..Default::default()
+        };
+
+        for semilla in 0..20 {
+            let laberinto = generar(&parametros, semilla).unwrap();
+            assert_eq!(
+                laberinto.to_string().parse::<Laberinto>().unwrap(),
+                laberinto
+            );
+        }
+    }
+
+    #[test]
+    fn test_parametros_invalidos() {
+        let sin_filas = ParametrosGeneracion {
+            alto: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            generar(&sin_filas, 0),
+            Err(GeneracionError::TamanoInvalido { ancho: 7, alto: 0 })
+        );
+
+        let negativa = ParametrosGeneracion {
+            desvios: -0.1,
+            ..Default::default()
+        };
+        assert_eq!(
+            generar(&negativa, 0),
+            Err(GeneracionError::DensidadInvalida {
+                nombre: "desvíos",
+                valor: -0.1
+            })
+        );
+
+        let paredes = ParametrosGeneracion {
+            paredes: DisposicionParedes::Aleatoria(1.5),
+            ..Default::default()
+        };
+        assert!(matches!(
+            generar(&paredes, 0),
+            Err(GeneracionError::DensidadInvalida {
+                nombre: "paredes",
+                ..
+            })
+        ));
+
+        let excesivas = ParametrosGeneracion {
+            rocas: 0.6,
+            enemigos: 0.6,
+            ..Default::default()
+        };
+        assert!(matches!(
+            generar(&excesivas, 0),
+            Err(GeneracionError::DensidadesExcesivas(_))
+        ));
+    }
+
+    fn enemigos(laberinto: &Laberinto) -> usize {
+        laberinto
+            .grid
+            .iter()
+            .flatten()
+            .filter(|celda| matches!(celda.objeto, Objeto::Enemigo(_)))
+            .count()
+    }
+
+    #[test]
+    fn test_puzzle_se_resuelve_con_su_solucion() {
+        for detonaciones in 1..=2 {
+            let parametros = ParametrosPuzzle {
+                detonaciones,
+                ..Default::default()
+            };
+
+            for semilla in 0..5 {
+                let puzzle = generar_puzzle(&parametros, semilla).unwrap();
+                assert_eq!(puzzle.solucion.len(), detonaciones);
+                assert!(enemigos(&puzzle.laberinto) >= detonaciones);
+
+                let mut laberinto = puzzle.laberinto.clone();
+                for &(x, y) in &puzzle.solucion {
+                    detonar_bomba(&mut laberinto, x, y).unwrap();
+                }
+                assert_eq!(enemigos(&laberinto), 0);
+
+                // Ninguna combinación con menos detonaciones ni otra con las mismas
+                let mut esperada = puzzle.solucion.clone();
+                esperada.sort_by_key(|&(x, y)| (y, x));
+                assert_eq!(soluciones(&puzzle.laberinto, detonaciones), vec![esperada]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_puzzle_misma_semilla_mismo_puzzle() {
+        let parametros = ParametrosPuzzle {
+            detonaciones: 2,
+            solucion_unica: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            generar_puzzle(&parametros, 9),
+            generar_puzzle(&parametros, 9)
+        );
+    }
+
+    #[test]
+    fn test_puzzle_imposible() {
+        let sin_bombas = ParametrosPuzzle {
+            laberinto: ParametrosGeneracion {
+                bombas: 0.0,
+                bombas_traspaso: 0.0,
+                ..Default::default()
+            },
+            intentos: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            generar_puzzle(&sin_bombas, 0),
+            Err(GeneracionError::PuzzleNoEncontrado { intentos: 10 })
+        );
+
+        let sin_detonaciones = ParametrosPuzzle {
+            detonaciones: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            generar_puzzle(&sin_detonaciones, 0),
+            Err(GeneracionError::SinDetonaciones)
+        );
+    }
+
+    #[test]
+    fn test_escribir_solucion() {
+        let puzzle = Puzzle {
+            laberinto: "B1 F1 B1 _ F1".parse().unwrap(),
+            solucion: vec![(0, 0)],
+            dificultad: Dificultad {
+                detonaciones: 1,
+                profundidad_cadena: 1,
+                bombas_encadenadas: 1,
+                desvios: 0,
+            },
+        };
+        let mut salida = Vec::new();
+        puzzle.escribir_solucion(&mut salida).unwrap();
+
+        assert_eq!(
+            String::from_utf8(salida).unwrap(),
+            "solucion: (0, 0)\n\
+             detonaciones: 1\n\
+             profundidad: 1\n\
+             encadenadas: 1\n\
+             desvios: 0\n\
+             dificultad: 2\n"
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use super::model::direccion::Direccion;
+use super::model::error::MovimientoError;
+use super::model::laberinto::Laberinto;
+use super::model::mejora::Mejora;
+use super::model::objeto::Objeto;
+use std::collections::HashSet;
+
+/// Resultado de mover a un jugador.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Movimiento {
+    /// Coordenada X a la que llegó el jugador.
+    pub x: usize,
+    /// Coordenada Y a la que llegó el jugador.
+    pub y: usize,
+    /// Mejora que el jugador recogió al llegar, si había una.
+    pub mejora: Option<Mejora>,
+}
+
+/// Características de las bombas de un jugador, que cambian al recoger mejoras.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EstadoJugador {
+    /// Alcance de las bombas que coloca el jugador.
+    pub alcance: i32,
+    /// Indica si las bombas del jugador son de traspaso.
+    pub traspaso: bool,
+    /// Cantidad de bombas que el jugador puede tener colocadas a la vez.
+    pub capacidad: usize,
+}
+
+impl Default for EstadoJugador {
+    /// Un jugador empieza con una bomba normal de alcance 1.
+    fn default() -> Self {
+        EstadoJugador {
+            alcance: 1,
+            traspaso: false,
+            capacidad: 1,
+        }
+    }
+}
+
+impl EstadoJugador {
+    /// Aplica el efecto de una mejora recogida.
+    pub fn aplicar(&mut self, mejora: Mejora) {
+        match mejora {
+            Mejora::Alcance => self.alcance += 1,
+            Mejora::Traspaso => self.traspaso = true,
+            Mejora::BombaExtra => self.capacidad += 1,
+        }
+    }
+
+    /// Crea una bomba con las características actuales del jugador y la `mecha` indicada.
+    pub fn bomba(&self, mecha: i32) -> Objeto {
+        if self.traspaso {
+            Objeto::BombaTraspasoTemporizada(self.alcance, mecha)
+        } else {
+            Objeto::BombaTemporizada(self.alcance, mecha)
+        }
+    }
+}
+
+/// Busca al jugador `id` y devuelve su posición `(x, y)`.
+pub fn posicion_jugador(laberinto: &Laberinto, id: u32) -> Option<(usize, usize)> {
+    laberinto
+        .grid
+        .iter()
+        .flatten()
+        .find(|celda| matches!(celda.objeto, Objeto::Jugador(otro, _) if otro == id))
+        .map(|celda| (celda.x, celda.y))
+}
+
+/// Mueve al jugador `id` una celda en `direccion` y devuelve su nueva posición.
+///
+/// El jugador sólo puede moverse a celdas vacías o con una mejora, que recoge: las paredes, las
+/// rocas, los enemigos, las bombas y los otros jugadores lo bloquean. Un desvío lo empuja en su
+/// dirección, igual que a una explosión, hasta llegar a una celda que no sea un desvío. Si el
+/// movimiento no es posible el laberinto no se modifica.
+///
+/// La mejora recogida se indica en el [`Movimiento`] devuelto; aplicar su efecto le
+/// corresponde a quien lleva el estado del jugador, por ejemplo con [`EstadoJugador::aplicar`].
+///
+/// Si el jugador estaba parado sobre una bomba que dejó, la bomba queda en la celda.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::jugador::mover_jugador;
+/// use bomberman_r::bomberman::model::direccion::Direccion;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
+/// use bomberman_r::bomberman::model::mejora::Mejora;
+///
+/// let mut laberinto: Laberinto = "P1 DD _\n_ MA W".parse().unwrap();
+/// let movimiento = mover_jugador(&mut laberinto, 1, &Direccion::Derecha).unwrap();
+/// assert_eq!((movimiento.x, movimiento.y), (1, 1));
+/// assert_eq!(movimiento.mejora, Some(Mejora::Alcance));
+/// assert_eq!(laberinto.to_string(), "_ DD _\n_ P1 W");
+/// ```
+pub fn mover_jugador(
+    laberinto: &mut Laberinto,
+    id: u32,
+    direccion: &Direccion,
+) -> Result<Movimiento, MovimientoError> {
+    let (x, y) = posicion_jugador(laberinto, id).ok_or(MovimientoError::JugadorInexistente(id))?;
+
+    // Sacar al jugador de su celda, para que el camino pueda volver a pasar por ella
+    let celda = &mut laberinto.grid[y][x];
+    let jugador = std::mem::replace(&mut celda.objeto, Objeto::Vacio);
+    if let Objeto::Jugador(_, Some(bomba)) = &jugador {
+        celda.objeto = (**bomba).clone();
+    }
+
+    match calcular_destino(laberinto, x, y, direccion) {
+        Ok((nuevo_x, nuevo_y)) => {
+            let destino = &mut laberinto.grid[nuevo_y][nuevo_x].objeto;
+            let mejora = match destino {
+                Objeto::Mejora(mejora) => Some(*mejora),
+                _ => None,
+            };
+            *destino = Objeto::Jugador(id, None);
+            Ok(Movimiento {
+                x: nuevo_x,
+                y: nuevo_y,
+                mejora,
+            })
+        }
+        Err(error) => {
+            laberinto.grid[y][x].objeto = jugador;
+            Err(error)
+        }
+    }
+}
+
+/// Deja `bomba` en la celda del jugador `id`. La bomba queda debajo del jugador hasta que
+/// éste se mueva; si explota antes, el jugador muere con ella.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::jugador::soltar_bomba;
+/// use bomberman_r::bomberman::model::laberinto::Laberinto;
+/// use bomberman_r::bomberman::model::objeto::Objeto;
+///
+/// let mut laberinto: Laberinto = "_ P2".parse().unwrap();
+/// soltar_bomba(&mut laberinto, 2, Objeto::BombaTemporizada(1, 3)).unwrap();
+/// assert_eq!(laberinto.to_string(), "_ P2+B1:3");
+/// ```
+pub fn soltar_bomba(
+    laberinto: &mut Laberinto,
+    id: u32,
+    bomba: Objeto,
+) -> Result<(), MovimientoError> {
+    if bomba.como_bomba().is_none() || matches!(bomba, Objeto::Jugador(..)) {
+        return Err(MovimientoError::NoEsUnaBomba(bomba));
+    }
+    let (x, y) = posicion_jugador(laberinto, id).ok_or(MovimientoError::JugadorInexistente(id))?;
+
+    if let Objeto::Jugador(_, debajo) = &mut laberinto.grid[y][x].objeto {
+        if debajo.is_some() {
+            return Err(MovimientoError::BombaYaColocada(id));
+        }
+        *debajo = Some(Box::new(bomba));
+    }
+    Ok(())
+}
+
+// Calcula la celda a la que llega un jugador que parte de `(x, y)` en `direccion`, siguiendo
+// los desvíos del camino.
+fn calcular_destino(
+    laberinto: &Laberinto,
+    mut x: usize,
+    mut y: usize,
+    direccion: &Direccion,
+) -> Result<(usize, usize), MovimientoError> {
+    let (mut dx, mut dy) = direccion.desplazamiento();
+    let mut desvios = HashSet::new();
+    loop {
+        x = x.wrapping_add(dx as usize);
+        y = y.wrapping_add(dy as usize);
+        if x >= laberinto.ancho || y >= laberinto.alto {
+            return Err(MovimientoError::FueraDelLaberinto);
+        }
+
+        match &laberinto.grid[y][x].objeto {
+            Objeto::Vacio | Objeto::Mejora(_) => return Ok((x, y)),
+            Objeto::Desvio(desvio) => {
+                if !desvios.insert((x, y)) {
+                    return Err(MovimientoError::CicloDeDesvio { x, y });
+                }
+                (dx, dy) = desvio.desplazamiento();
+            }
+            objeto => {
+                return Err(MovimientoError::Bloqueado {
+                    x,
+                    y,
+                    objeto: objeto.clone(),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bomberman::detonar_bomba_con_traza;
+    use crate::bomberman::model::evento::EventoExplosion;
+
+    fn desde_texto(texto: &str) -> Laberinto {
+        texto.parse().unwrap()
+    }
+
+    #[test]
+    fn test_movimiento_simple() {
+        let mut laberinto = desde_texto("_ _\nP1 _");
+
+        assert_eq!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Arriba).map(|m| (m.x, m.y)),
+            Ok((0, 0))
+        );
+        assert_eq!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Derecha).map(|m| (m.x, m.y)),
+            Ok((1, 0))
+        );
+        assert_eq!(laberinto.to_string(), "_ P1\n_ _");
+        assert_eq!(posicion_jugador(&laberinto, 1), Some((1, 0)));
+        assert_eq!(posicion_jugador(&laberinto, 2), None);
+    }
+
+    #[test]
+    fn test_obstaculos_bloquean_al_jugador() {
+        let mut laberinto = desde_texto("W P1 R\nF1 B2 P2");
+
+        for (direccion, objeto) in [
+            (Direccion::Izquierda, Objeto::Pared),
+            (Direccion::Derecha, Objeto::Roca),
+            (Direccion::Abajo, Objeto::Bomba(2)),
+        ] {
+            assert!(matches!(
+                mover_jugador(&mut laberinto, 1, &direccion),
+                Err(MovimientoError::Bloqueado { objeto: ref bloqueo, .. }) if *bloqueo == objeto
+            ));
+        }
+        assert!(matches!(
+            mover_jugador(&mut laberinto, 2, &Direccion::Izquierda),
+            Err(MovimientoError::Bloqueado { x: 1, y: 1, .. })
+        ));
+        assert_eq!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Arriba),
+            Err(MovimientoError::FueraDelLaberinto)
+        );
+        assert_eq!(
+            mover_jugador(&mut laberinto, 3, &Direccion::Arriba),
+            Err(MovimientoError::JugadorInexistente(3))
+        );
+        assert_eq!(laberinto.to_string(), "W P1 R\nF1 B2 P2");
+    }
+
+    #[test]
+    fn test_desvios_empujan_al_jugador() {
+        let mut laberinto = desde_texto("P1 DR DD\n_ _ _");
+        assert_eq!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Derecha).map(|m| (m.x, m.y)),
+            Ok((2, 1))
+        );
+
+        // El camino puede volver a pasar por la celda de la que salió el jugador
+        let mut laberinto = desde_texto("_ P1 DL");
+        assert_eq!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Derecha).map(|m| (m.x, m.y)),
+            Ok((1, 0))
+        );
+
+        let mut laberinto = desde_texto("P1 DD _\n_ DU _");
+        assert_eq!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Derecha),
+            Err(MovimientoError::CicloDeDesvio { x: 1, y: 0 })
+        );
+
+        let mut laberinto = desde_texto("P1 DU");
+        assert_eq!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Derecha),
+            Err(MovimientoError::FueraDelLaberinto)
+        );
+        assert_eq!(laberinto.to_string(), "P1 DU");
+    }
+
+    #[test]
+    fn test_soltar_bomba_y_alejarse() {
+        let mut laberinto = desde_texto("P1 _");
+
+        soltar_bomba(&mut laberinto, 1, Objeto::Bomba(1)).unwrap();
+        assert_eq!(
+            soltar_bomba(&mut laberinto, 1, Objeto::Bomba(1)),
+            Err(MovimientoError::BombaYaColocada(1))
+        );
+        assert_eq!(
+            soltar_bomba(&mut laberinto, 1, Objeto::Roca),
+            Err(MovimientoError::NoEsUnaBomba(Objeto::Roca))
+        );
+        assert_eq!(laberinto.to_string(), "P1+B1 _");
+
+        mover_jugador(&mut laberinto, 1, &Direccion::Derecha).unwrap();
+        assert_eq!(laberinto.to_string(), "B1 P1");
+        assert!(matches!(
+            mover_jugador(&mut laberinto, 1, &Direccion::Izquierda),
+            Err(MovimientoError::Bloqueado { x: 0, y: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_recoger_mejoras() {
+        let mut laberinto = desde_texto("P1 MB DD\n_ _ MT");
+
+        let movimiento = mover_jugador(&mut laberinto, 1, &Direccion::Derecha).unwrap();
+        assert_eq!(movimiento.mejora, Some(Mejora::BombaExtra));
+        let movimiento = mover_jugador(&mut laberinto, 1, &Direccion::Derecha).unwrap();
+        assert_eq!(
+            movimiento,
+            Movimiento {
+                x: 2,
+                y: 1,
+                mejora: Some(Mejora::Traspaso)
+            }
+        );
+        assert_eq!(laberinto.to_string(), "_ _ DD\n_ _ P1");
+
+        let mut estado = EstadoJugador::default();
+        assert_eq!(estado.bomba(3), Objeto::BombaTemporizada(1, 3));
+        for mejora in [Mejora::BombaExtra, Mejora::Traspaso, Mejora::Alcance] {
+            estado.aplicar(mejora);
+        }
+        assert_eq!(estado.capacidad, 2);
+        assert_eq!(estado.bomba(3), Objeto::BombaTraspasoTemporizada(2, 3));
+    }
+
+    #[test]
+    fn test_explosion_destruye_mejoras_a_la_vista() {
+        let mut laberinto = desde_texto("B2 MA C1+MT");
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+        assert!(eventos.contains(&EventoExplosion::MejoraDestruida { x: 1, y: 0 }));
+        assert_eq!(laberinto.to_string(), "_ _ MT");
+    }
+
+    #[test]
+    fn test_explosion_elimina_jugadores() {
+        let mut laberinto = desde_texto("B1 P1 _ P2+B1 P3");
+
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+        assert!(eventos.contains(&EventoExplosion::JugadorEliminado {
+            x: 1,
+            y: 0,
+            jugador: 1
+        }));
+        assert_eq!(laberinto.to_string(), "_ _ _ P2+B1 P3");
+
+        // El jugador muere con su propia bomba, que alcanza al jugador de al lado
+        let eventos = detonar_bomba_con_traza(&mut laberinto, 3, 0).unwrap();
+        assert!(eventos.contains(&EventoExplosion::JugadorEliminado {
+            x: 3,
+            y: 0,
+            jugador: 2
+        }));
+        assert_eq!(laberinto.to_string(), "_ _ _ _ _");
+    }
+
+    #[test]
+    fn test_explosion_sobre_jugador_con_bomba_encadena() {
+        let mut laberinto = desde_texto("B1 P1+B1 F1");
+
+        detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+        assert_eq!(laberinto.to_string(), "_ _ _");
+    }
+}
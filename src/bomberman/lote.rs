@@ -0,0 +1,269 @@
+use super::model::error::ManifiestoError;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Coordenadas `(x, y)` de las bombas a detonar en cada archivo, por nombre de archivo.
+pub type Manifiesto = BTreeMap<String, Vec<(usize, usize)>>;
+
+/// Lee un manifiesto de procesamiento por lotes.
+///
+/// Cada línea tiene el nombre de un archivo seguido de las coordenadas `x y` de las bombas a
+/// detonar, en orden, igual que en la forma posicional de la línea de comandos. Las líneas
+/// vacías y las que empiezan con `#` se ignoran.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::lote::leer_manifiesto;
+///
+/// let manifiesto = leer_manifiesto("# ejemplos\nejemplo_1.txt 0 0 2 2\n".as_bytes()).unwrap();
+/// assert_eq!(manifiesto["ejemplo_1.txt"], vec![(0, 0), (2, 2)]);
+/// ```
+pub fn leer_manifiesto(lector: impl BufRead) -> Result<Manifiesto, ManifiestoError> {
+    let mut manifiesto = Manifiesto::new();
+
+    for (indice, linea) in lector.lines().enumerate() {
+        let linea = linea?;
+        let texto = linea.trim();
+        if texto.is_empty() || texto.starts_with('#') {
+            continue;
+        }
+        let invalida = || ManifiestoError::LineaInvalida {
+            linea: indice + 1,
+            texto: texto.to_string(),
+        };
+
+        let mut tokens = texto.split_whitespace();
+        let archivo = tokens.next().ok_or_else(invalida)?;
+        let numeros = tokens
+            .map(|token| token.parse::<usize>().map_err(|_| invalida()))
+            .collect::<Result<Vec<usize>, _>>()?;
+        if numeros.is_empty() || numeros.len() % 2 != 0 {
+            return Err(invalida());
+        }
+
+        let coordenadas = numeros.chunks(2).map(|par| (par[0], par[1])).collect();
+        if manifiesto
+            .insert(archivo.to_string(), coordenadas)
+            .is_some()
+        {
+            return Err(ManifiestoError::ArchivoRepetido {
+                linea: indice + 1,
+                archivo: archivo.to_string(),
+            });
+        }
+    }
+
+    Ok(manifiesto)
+}
+
+/// Devuelve, ordenados, los archivos que indica `patron`:
+///
+/// - si es un directorio, todos los archivos que contiene;
+/// - si el nombre tiene `*` o `?`, los archivos de su directorio que [`coinciden`](coincide)
+///   con él;
+/// - si no, la misma ruta.
+pub fn expandir_patron(patron: &str) -> io::Result<Vec<PathBuf>> {
+    let ruta = Path::new(patron);
+    if ruta.is_dir() {
+        return archivos_de(ruta, |_| true);
+    }
+
+    let nombre = ruta
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if !nombre.contains(['*', '?']) {
+        return Ok(vec![ruta.to_path_buf()]);
+    }
+    let directorio = match ruta.parent() {
+        Some(directorio) if !directorio.as_os_str().is_empty() => directorio,
+        _ => Path::new("."),
+    };
+    archivos_de(directorio, |archivo| coincide(nombre, archivo))
+}
+
+fn archivos_de(directorio: &Path, filtro: impl Fn(&str) -> bool) -> io::Result<Vec<PathBuf>> {
+    let mut archivos = Vec::new();
+    for entrada in fs::read_dir(directorio)? {
+        let entrada = entrada?;
+        let nombre = entrada.file_name();
+        if entrada.file_type()?.is_file() && nombre.to_str().is_some_and(&filtro) {
+            archivos.push(entrada.path());
+        }
+    }
+    archivos.sort();
+    Ok(archivos)
+}
+
+/// Indica si `nombre` coincide con `patron`, donde `*` representa cualquier cantidad de
+/// caracteres y `?` exactamente uno.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::lote::coincide;
+///
+/// assert!(coincide("ejemplo_?.txt", "ejemplo_1.txt"));
+/// assert!(!coincide("*.txt", "ejemplo_1.md"));
+/// ```
+pub fn coincide(patron: &str, nombre: &str) -> bool {
+    let patron: Vec<char> = patron.chars().collect();
+    let nombre: Vec<char> = nombre.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Posición del último `*` y del carácter del nombre desde el que se probó
+    let mut ultimo_asterisco = None;
+
+    while n < nombre.len() {
+        if p < patron.len() && (patron[p] == '?' || patron[p] == nombre[n]) {
+            p += 1;
+            n += 1;
+        } else if p < patron.len() && patron[p] == '*' {
+            ultimo_asterisco = Some((p, n));
+            p += 1;
+        } else if let Some((asterisco, desde)) = ultimo_asterisco {
+            // Que el último `*` abarque un carácter más y volver a probar
+            p = asterisco + 1;
+            n = desde + 1;
+            ultimo_asterisco = Some((asterisco, desde + 1));
+        } else {
+            return false;
+        }
+    }
+    patron[p..].iter().all(|&c| c == '*')
+}
+
+/// Aplica `f` a cada elemento usando hasta `hilos` hilos y devuelve los resultados en el mismo
+/// orden que los elementos.
+///
+/// Cada hilo toma el siguiente elemento pendiente cuando termina el anterior, así que los
+/// elementos que tardan más no demoran a los demás hilos.
+pub fn en_paralelo<T: Sync, R: Send>(
+    elementos: &[T],
+    hilos: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let siguiente = AtomicUsize::new(0);
+    let hilos = hilos.clamp(1, elementos.len().max(1));
+
+    let mut resultados: Vec<(usize, R)> = thread::scope(|s| {
+        let manejadores: Vec<_> = (0..hilos)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut propios = Vec::new();
+                    loop {
+                        let indice = siguiente.fetch_add(1, Ordering::Relaxed);
+                        let Some(elemento) = elementos.get(indice) else {
+                            return propios;
+                        };
+                        propios.push((indice, f(elemento)));
+                    }
+                })
+            })
+            .collect();
+
+        manejadores
+            .into_iter()
+            .flat_map(|manejador| {
+                manejador
+                    .join()
+                    .unwrap_or_else(|error| panic::resume_unwind(error))
+            })
+            .collect()
+    });
+
+    resultados.sort_by_key(|(indice, _)| *indice);
+    resultados
+        .into_iter()
+        .map(|(_, resultado)| resultado)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leer_manifiesto() {
+        let manifiesto = leer_manifiesto(
+            "\
+            # comentario\n\
+            \n\
+            a.txt 0 0\n\
+            b.txt   1 2 3 4 \n"
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(manifiesto.len(), 2);
+        assert_eq!(manifiesto["a.txt"], vec![(0, 0)]);
+        assert_eq!(manifiesto["b.txt"], vec![(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn test_leer_manifiesto_invalido() {
+        for texto in ["a.txt", "a.txt 1", "a.txt 1 x", "a.txt -1 0"] {
+            match leer_manifiesto(format!("b.txt 0 0\n{}", texto).as_bytes()) {
+                Err(ManifiestoError::LineaInvalida { linea: 2, .. }) => {}
+                otro => panic!("{}: {:?}", texto, otro),
+            }
+        }
+
+        assert!(matches!(
+            leer_manifiesto("a.txt 0 0\na.txt 1 1".as_bytes()),
+            Err(ManifiestoError::ArchivoRepetido { linea: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_coincide() {
+        assert!(coincide("*", ""));
+        assert!(coincide("*.txt", "ejemplo_1.txt"));
+        assert!(coincide("e*_*.txt", "ejemplo_1.txt"));
+        assert!(coincide("*a*a", "banana"));
+        assert!(coincide("ejemplo_?.txt", "ejemplo_5.txt"));
+        assert!(!coincide("ejemplo_?.txt", "ejemplo_10.txt"));
+        assert!(!coincide("*.txt", "ejemplo.txt.bak"));
+        assert!(!coincide("a", ""));
+    }
+
+    #[test]
+    fn test_expandir_patron() {
+        let ejemplos = expandir_patron("ejemplos/ejemplo_*.txt").unwrap();
+        let nombres: Vec<_> = ejemplos
+            .iter()
+            .map(|ruta| ruta.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            nombres,
+            [
+                "ejemplo_1.txt",
+                "ejemplo_2.txt",
+                "ejemplo_3.txt",
+                "ejemplo_4.txt",
+                "ejemplo_5.txt"
+            ]
+        );
+
+        assert!(expandir_patron("ejemplos").unwrap().len() > ejemplos.len());
+        assert_eq!(
+            expandir_patron("no_existe.txt").unwrap(),
+            [PathBuf::from("no_existe.txt")]
+        );
+    }
+
+    #[test]
+    fn test_en_paralelo_mantiene_el_orden() {
+        let numeros: Vec<u64> = (0..100).collect();
+
+        let cuadrados = en_paralelo(&numeros, 4, |n| n * n);
+
+        assert_eq!(cuadrados, numeros.iter().map(|n| n * n).collect::<Vec<_>>());
+        assert!(en_paralelo(&[] as &[u64], 4, |n| *n).is_empty());
+    }
+}
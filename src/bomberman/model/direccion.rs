@@ -1,5 +1,7 @@
+use std::fmt;
+
 /// Enumeración que representa las direcciones posibles.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Direccion {
     /// Representa la dirección hacia la izquierda.
     Izquierda,
@@ -12,4 +14,92 @@ pub enum Direccion {
 
     /// Representa la dirección hacia abajo.
     Abajo,
+
+    /// Representa la diagonal hacia arriba y a la derecha.
+    NorEste,
+
+    /// Representa la diagonal hacia arriba y a la izquierda.
+    NorOeste,
+
+    /// Representa la diagonal hacia abajo y a la derecha.
+    SurEste,
+
+    /// Representa la diagonal hacia abajo y a la izquierda.
+    SurOeste,
+}
+
+impl Direccion {
+    /// Las cuatro direcciones, en el mismo orden en que se propagan las explosiones.
+    pub const CARDINALES: [Direccion; 4] = [
+        Direccion::Derecha,
+        Direccion::Izquierda,
+        Direccion::Abajo,
+        Direccion::Arriba,
+    ];
+
+    /// Las cuatro diagonales, en el mismo orden en que se propagan las explosiones.
+    pub const DIAGONALES: [Direccion; 4] = [
+        Direccion::SurEste,
+        Direccion::NorOeste,
+        Direccion::SurOeste,
+        Direccion::NorEste,
+    ];
+
+    /// Las ocho direcciones: primero las cardinales y después las diagonales.
+    pub const TODAS: [Direccion; 8] = [
+        Direccion::Derecha,
+        Direccion::Izquierda,
+        Direccion::Abajo,
+        Direccion::Arriba,
+        Direccion::SurEste,
+        Direccion::NorOeste,
+        Direccion::SurOeste,
+        Direccion::NorEste,
+    ];
+
+    /// Devuelve el desplazamiento `(dx, dy)` de un paso en esta dirección. El eje Y crece
+    /// hacia abajo.
+    pub fn desplazamiento(&self) -> (i32, i32) {
+        match self {
+            Direccion::Izquierda => (-1, 0),
+            Direccion::Derecha => (1, 0),
+            Direccion::Arriba => (0, -1),
+            Direccion::Abajo => (0, 1),
+            Direccion::NorEste => (1, -1),
+            Direccion::NorOeste => (-1, -1),
+            Direccion::SurEste => (1, 1),
+            Direccion::SurOeste => (-1, 1),
+        }
+    }
+
+    /// Devuelve la dirección contraria.
+    pub fn opuesta(&self) -> Direccion {
+        match self {
+            Direccion::Izquierda => Direccion::Derecha,
+            Direccion::Derecha => Direccion::Izquierda,
+            Direccion::Arriba => Direccion::Abajo,
+            Direccion::Abajo => Direccion::Arriba,
+            Direccion::NorEste => Direccion::SurOeste,
+            Direccion::NorOeste => Direccion::SurEste,
+            Direccion::SurEste => Direccion::NorOeste,
+            Direccion::SurOeste => Direccion::NorEste,
+        }
+    }
+}
+
+impl fmt::Display for Direccion {
+    /// Escribe las letras con las que se representa la dirección en los archivos de laberinto.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letras = match self {
+            Direccion::Izquierda => "L",
+            Direccion::Derecha => "R",
+            Direccion::Arriba => "U",
+            Direccion::Abajo => "D",
+            Direccion::NorEste => "NE",
+            Direccion::NorOeste => "NW",
+            Direccion::SurEste => "SE",
+            Direccion::SurOeste => "SW",
+        };
+        write!(f, "{}", letras)
+    }
 }
@@ -0,0 +1,315 @@
+use std::fmt;
+use std::io;
+
+use super::objeto::Objeto;
+
+/// Errores que pueden ocurrir al cargar un laberinto.
+///
+/// Las variantes que provienen del contenido del archivo indican la línea y la columna
+/// (ambas comenzando en 1) y el texto que provocó el error.
+#[derive(Debug)]
+pub enum LaberintoError {
+    /// Se encontró un carácter que no corresponde a ningún objeto.
+    CaracterDesconocido {
+        linea: usize,
+        columna: usize,
+        token: String,
+    },
+
+    /// El número que acompaña a un objeto no es válido (por ejemplo, no entra en un `i32`).
+    NumeroInvalido {
+        linea: usize,
+        columna: usize,
+        token: String,
+    },
+
+    /// La dirección de un desvío no es ninguna de `L`, `R`, `U`, `D`, `NE`, `NW`, `SE` o `SW`.
+    DireccionInvalida {
+        linea: usize,
+        columna: usize,
+        token: String,
+    },
+
+    /// El tipo de una mejora no es ninguno de `A`, `T` o `B`.
+    MejoraInvalida {
+        linea: usize,
+        columna: usize,
+        token: String,
+    },
+
+    /// El tablero no es rectangular: alguna fila no tiene tantas columnas como la primera.
+    /// `filas_incorrectas` contiene, para cada fila inválida, su línea (comenzando en 1) y la
+    /// cantidad de columnas que tiene.
+    TableroIrregular {
+        columnas: usize,
+        filas_incorrectas: Vec<(usize, usize)>,
+    },
+
+    /// El teletransportador `id` no aparece exactamente dos veces. `posiciones` contiene las
+    /// coordenadas `(x, y)` en las que aparece.
+    TeletransportadorSinPareja {
+        id: u8,
+        posiciones: Vec<(usize, usize)>,
+    },
+
+    /// El jugador `id` aparece más de una vez. `linea` y `columna` (comenzando en 1) indican
+    /// dónde aparece repetido; la columna cuenta objetos de la fila, no caracteres.
+    JugadorRepetido {
+        id: u32,
+        linea: usize,
+        columna: usize,
+    },
+
+    /// El archivo no contiene ninguna fila.
+    ArchivoVacio,
+
+    /// Error de entrada/salida al leer el archivo.
+    Io(io::Error),
+}
+
+impl fmt::Display for LaberintoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaberintoError::CaracterDesconocido {
+                linea,
+                columna,
+                token,
+            } => write!(
+                f,
+                "línea {}, columna {}: carácter desconocido '{}'",
+                linea, columna, token
+            ),
+            LaberintoError::NumeroInvalido {
+                linea,
+                columna,
+                token,
+            } => write!(
+                f,
+                "línea {}, columna {}: número inválido '{}'",
+                linea, columna, token
+            ),
+            LaberintoError::DireccionInvalida {
+                linea,
+                columna,
+                token,
+            } => write!(
+                f,
+                "línea {}, columna {}: dirección inválida '{}'",
+                linea, columna, token
+            ),
+            LaberintoError::MejoraInvalida {
+                linea,
+                columna,
+                token,
+            } => write!(
+                f,
+                "línea {}, columna {}: mejora inválida '{}'",
+                linea, columna, token
+            ),
+            LaberintoError::TableroIrregular {
+                columnas,
+                filas_incorrectas,
+            } => {
+                write!(
+                    f,
+                    "el tablero no es rectangular: se esperaban {} columnas por fila",
+                    columnas
+                )?;
+                for (linea, columnas) in filas_incorrectas {
+                    write!(f, "; la línea {} tiene {}", linea, columnas)?;
+                }
+                Ok(())
+            }
+            LaberintoError::TeletransportadorSinPareja { id, posiciones } => {
+                write!(
+                    f,
+                    "el teletransportador T{} debe aparecer exactamente dos veces, pero aparece {}",
+                    id,
+                    posiciones.len()
+                )?;
+                for (indice, (x, y)) in posiciones.iter().enumerate() {
+                    let separador = if indice == 0 { ":" } else { "," };
+                    write!(f, "{} ({}, {})", separador, x, y)?;
+                }
+                Ok(())
+            }
+            LaberintoError::JugadorRepetido { id, linea, columna } => write!(
+                f,
+                "línea {}, columna {}: el jugador P{} aparece más de una vez",
+                linea, columna, id
+            ),
+            LaberintoError::ArchivoVacio => write!(f, "el archivo está vacío"),
+            LaberintoError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LaberintoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LaberintoError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LaberintoError {
+    fn from(error: io::Error) -> Self {
+        LaberintoError::Io(error)
+    }
+}
+
+/// Errores que pueden ocurrir al mover a un jugador o al dejar una bomba.
+#[derive(Debug, PartialEq)]
+pub enum MovimientoError {
+    /// No hay ningún jugador con ese número en el laberinto.
+    JugadorInexistente(u32),
+
+    /// No hay ningún enemigo en la celda `(x, y)`.
+    EnemigoInexistente { x: usize, y: usize },
+
+    /// El movimiento, o un desvío en el camino, saldría del laberinto.
+    FueraDelLaberinto,
+
+    /// La celda `(x, y)` está ocupada por `objeto`.
+    Bloqueado { x: usize, y: usize, objeto: Objeto },
+
+    /// Los desvíos del camino vuelven al desvío en `(x, y)`, así que el jugador no se detendría
+    /// nunca.
+    CicloDeDesvio { x: usize, y: usize },
+
+    /// El jugador ya dejó una bomba en su celda y todavía no se movió.
+    BombaYaColocada(u32),
+
+    /// El jugador ya tiene colocadas todas las bombas que le permite su capacidad.
+    SinBombasDisponibles(u32),
+
+    /// El objeto que se quiso dejar no es una bomba.
+    NoEsUnaBomba(Objeto),
+}
+
+impl fmt::Display for MovimientoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovimientoError::JugadorInexistente(id) => write!(f, "no existe el jugador {}", id),
+            MovimientoError::EnemigoInexistente { x, y } => {
+                write!(f, "no hay ningún enemigo en ({}, {})", x, y)
+            }
+            MovimientoError::FueraDelLaberinto => write!(f, "el movimiento sale del laberinto"),
+            MovimientoError::Bloqueado { x, y, objeto } => {
+                write!(f, "la celda ({}, {}) está ocupada por '{}'", x, y, objeto)
+            }
+            MovimientoError::CicloDeDesvio { x, y } => {
+                write!(f, "los desvíos forman un ciclo en ({}, {})", x, y)
+            }
+            MovimientoError::BombaYaColocada(id) => {
+                write!(f, "el jugador {} ya dejó una bomba en su celda", id)
+            }
+            MovimientoError::SinBombasDisponibles(id) => {
+                write!(f, "el jugador {} no tiene bombas disponibles", id)
+            }
+            MovimientoError::NoEsUnaBomba(objeto) => write!(f, "'{}' no es una bomba", objeto),
+        }
+    }
+}
+
+impl std::error::Error for MovimientoError {}
+
+/// Errores que pueden ocurrir al generar un laberinto.
+#[derive(Debug, PartialEq)]
+pub enum GeneracionError {
+    /// El laberinto tiene que tener al menos una fila y una columna.
+    TamanoInvalido { ancho: usize, alto: usize },
+
+    /// La densidad `nombre` no está entre 0 y 1.
+    DensidadInvalida { nombre: &'static str, valor: f64 },
+
+    /// Las densidades de los objetos suman más de 1.
+    DensidadesExcesivas(f64),
+
+    /// Un puzzle tiene que resolverse con al menos una detonación.
+    SinDetonaciones,
+
+    /// Ninguno de los `intentos` produjo un puzzle que cumpla los parámetros.
+    PuzzleNoEncontrado { intentos: usize },
+}
+
+impl fmt::Display for GeneracionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneracionError::TamanoInvalido { ancho, alto } => {
+                write!(f, "el tamaño {}x{} no es válido", ancho, alto)
+            }
+            GeneracionError::DensidadInvalida { nombre, valor } => {
+                write!(
+                    f,
+                    "la densidad de {} debe estar entre 0 y 1, pero es {}",
+                    nombre, valor
+                )
+            }
+            GeneracionError::DensidadesExcesivas(total) => {
+                write!(f, "las densidades de los objetos suman {}, más de 1", total)
+            }
+            GeneracionError::SinDetonaciones => {
+                write!(f, "un puzzle necesita al menos una detonación")
+            }
+            GeneracionError::PuzzleNoEncontrado { intentos } => {
+                write!(
+                    f,
+                    "no se encontró un puzzle válido en {} intentos",
+                    intentos
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeneracionError {}
+
+/// Errores que pueden ocurrir al leer un manifiesto de procesamiento por lotes.
+#[derive(Debug)]
+pub enum ManifiestoError {
+    /// La línea `linea` (comenzando en 1) no tiene la forma `archivo x y [x y ...]`.
+    LineaInvalida { linea: usize, texto: String },
+
+    /// El archivo aparece en más de una línea; `linea` es la segunda aparición.
+    ArchivoRepetido { linea: usize, archivo: String },
+
+    /// Error de entrada/salida al leer el manifiesto.
+    Io(io::Error),
+}
+
+impl fmt::Display for ManifiestoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifiestoError::LineaInvalida { linea, texto } => write!(
+                f,
+                "línea {}: se esperaba 'archivo x y [x y ...]', pero se encontró '{}'",
+                linea, texto
+            ),
+            ManifiestoError::ArchivoRepetido { linea, archivo } => {
+                write!(
+                    f,
+                    "línea {}: el archivo '{}' ya apareció antes",
+                    linea, archivo
+                )
+            }
+            ManifiestoError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ManifiestoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ManifiestoError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ManifiestoError {
+    fn from(error: io::Error) -> Self {
+        ManifiestoError::Io(error)
+    }
+}
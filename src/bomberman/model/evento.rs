@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use super::direccion::Direccion;
+use super::objeto::Objeto;
+
+/// Evento ocurrido durante la propagación de una explosión.
+///
+/// Los eventos se registran en el orden en que la explosión los produce, por lo que pueden
+/// usarse para depurar niveles o para animar la explosión paso a paso.
+#[derive(Debug, PartialEq, Clone)]
+pub enum EventoExplosion {
+    /// Una bomba explotó. `profundidad` es 0 para la bomba detonada y aumenta en 1 por cada
+    /// bomba encadenada.
+    BombaActivada {
+        x: usize,
+        y: usize,
+        profundidad: usize,
+    },
+
+    /// La explosión alcanzó la celda.
+    CeldaAlcanzada { x: usize, y: usize },
+
+    /// Un enemigo perdió una vida y sigue con `vidas`.
+    EnemigoDaniado { x: usize, y: usize, vidas: i32 },
+
+    /// Un enemigo perdió su última vida y fue eliminado.
+    EnemigoEliminado { x: usize, y: usize },
+
+    /// Una caja perdió resistencia y sigue con `resistencia`.
+    CajaDaniada {
+        x: usize,
+        y: usize,
+        resistencia: i32,
+    },
+
+    /// Una caja perdió su última resistencia y se rompió. La explosión se detiene igual en
+    /// ella; el objeto que escondía aparece cuando termina la explosión.
+    CajaRota { x: usize, y: usize },
+
+    /// La explosión destruyó una mejora que estaba a la vista.
+    MejoraDestruida { x: usize, y: usize },
+
+    /// Un jugador fue alcanzado por la explosión y eliminado.
+    JugadorEliminado { x: usize, y: usize, jugador: u32 },
+
+    /// Un desvío cambió la dirección de la explosión.
+    Desviada {
+        x: usize,
+        y: usize,
+        direccion: Direccion,
+    },
+
+    /// La explosión entró en el teletransportador en `(x, y)` y sigue desde su pareja en
+    /// `(destino_x, destino_y)`.
+    Teletransportada {
+        x: usize,
+        y: usize,
+        destino_x: usize,
+        destino_y: usize,
+    },
+
+    /// La explosión volvió al desvío en `(x, y)`, que ya había atravesado en la misma
+    /// dirección, y se detuvo para no recorrer el mismo ciclo de desvíos otra vez.
+    CicloDeDesvio { x: usize, y: usize },
+
+    /// La explosión se detuvo al llegar a una pared o a una roca.
+    Bloqueada { x: usize, y: usize, objeto: Objeto },
+}
+
+/// Resumen de los efectos de una explosión, calculado a partir de sus eventos.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ResumenExplosion {
+    /// Cantidad de enemigos que perdieron su última vida.
+    pub enemigos_eliminados: usize,
+    /// Cantidad de veces que un enemigo perdió una vida sin quedar eliminado. Un mismo enemigo
+    /// alcanzado por varias bombas de la cadena cuenta una vez por cada golpe.
+    pub enemigos_daniados: usize,
+    /// Cantidad de cajas rotas por la explosión.
+    pub cajas_rotas: usize,
+    /// Cantidad de mejoras destruidas por la explosión.
+    pub mejoras_destruidas: usize,
+    /// Cantidad de jugadores eliminados por la explosión.
+    pub jugadores_eliminados: usize,
+    /// Cantidad de bombas que explotaron por estar en el alcance de otra.
+    pub bombas_encadenadas: usize,
+    /// Cantidad de celdas distintas alcanzadas por la explosión.
+    pub celdas_alcanzadas: usize,
+}
+
+impl ResumenExplosion {
+    /// Calcula el resumen de una lista de eventos de explosión.
+    pub fn desde_eventos(eventos: &[EventoExplosion]) -> Self {
+        let mut resumen = ResumenExplosion::default();
+        let mut celdas = HashSet::new();
+        for evento in eventos {
+            match evento {
+                EventoExplosion::EnemigoEliminado { .. } => resumen.enemigos_eliminados += 1,
+                EventoExplosion::EnemigoDaniado { .. } => resumen.enemigos_daniados += 1,
+                EventoExplosion::CajaRota { .. } => resumen.cajas_rotas += 1,
+                EventoExplosion::MejoraDestruida { .. } => resumen.mejoras_destruidas += 1,
+                EventoExplosion::JugadorEliminado { .. } => resumen.jugadores_eliminados += 1,
+                EventoExplosion::BombaActivada { profundidad, .. } if *profundidad > 0 => {
+                    resumen.bombas_encadenadas += 1
+                }
+                EventoExplosion::CeldaAlcanzada { x, y } => {
+                    celdas.insert((*x, *y));
+                }
+                _ => {}
+            }
+        }
+        resumen.celdas_alcanzadas = celdas.len();
+        resumen
+    }
+}
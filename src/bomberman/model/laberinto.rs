@@ -1,13 +1,26 @@
 pub use super::celda::Celda;
 use super::direccion::Direccion;
+pub use super::error::LaberintoError;
+use super::evento::ResumenExplosion;
+use super::mejora::Mejora;
 use super::objeto::Objeto;
+use super::patron::Patron;
+use crate::bomberman::detonar_bomba_con_traza;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Write};
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::FromStr;
 
 /// Representa un laberinto compuesto por celdas con objetos.
+#[derive(Debug, PartialEq, Clone)]
 pub struct Laberinto {
-    /// Tamaño del laberinto (número de filas o columnas).
-    pub tamano: usize,
+    /// Ancho del laberinto (número de columnas).
+    pub ancho: usize,
+    /// Alto del laberinto (número de filas).
+    pub alto: usize,
     /// Matriz que contiene las celdas del laberinto.
     pub grid: Vec<Vec<Celda>>,
 }
@@ -15,77 +28,320 @@ pub struct Laberinto {
 impl Laberinto {
     /// Carga un laberinto desde un archivo especificado por `path`.
     ///
+    /// Equivale a [`Laberinto::from_path`]; se mantiene por compatibilidad.
+    ///
     /// # Argumentos
     ///
     /// * `path`: Ruta al archivo que contiene la definición del laberinto.
     ///
+    /// # Errores
+    ///
+    /// Devuelve un [`LaberintoError`] que indica la línea y la columna del primer objeto
+    /// inválido, o el motivo por el cual el archivo no pudo cargarse.
+    ///
     /// # Ejemplo
     ///
-    /// ```
+    /// ```no_run
+    /// use bomberman_r::bomberman::model::laberinto::Laberinto;
     ///
     /// let laberinto = Laberinto::cargar("laberinto.txt");
     /// ```
-    pub fn cargar(path: &str) -> Result<Self, io::Error> {
-        // Obtén el directorio actual
-        let directorio_actual = std::env::current_dir()?;
+    pub fn cargar(path: &str) -> Result<Self, LaberintoError> {
+        Self::from_path(path)
+    }
 
-        // Construye la ruta completa al archivo
-        let ruta_completa = directorio_actual.join(path);
+    /// Carga un laberinto desde el archivo ubicado en `path`.
+    ///
+    /// Las rutas relativas se resuelven respecto del directorio actual.
+    ///
+    /// # Errores
+    ///
+    /// Devuelve [`LaberintoError::Io`] si el archivo no existe o no puede leerse, y los mismos
+    /// errores que [`Laberinto::from_reader`] si su contenido no es válido.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, LaberintoError> {
+        let path = path.as_ref();
 
         // Comprueba si el archivo existe
-        if !ruta_completa.is_file() {
-            return Err(io::Error::new(
-                io::ErrorKind::NotFound,
-                "El archivo no existe",
-            ));
+        if !path.is_file() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "El archivo no existe").into());
         }
 
-        let ruta_completa_str = ruta_completa.to_str().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidData,
-                "La ruta del archivo no es válida",
-            )
-        })?;
+        let file = File::open(path)
+            .map_err(|e| io::Error::other(format!("Error al abrir el archivo: {}", e)))?;
 
-        let lineas = Self::leer_lineas(ruta_completa_str)?;
+        Self::from_reader(BufReader::new(file))
+    }
 
+    /// Carga un laberinto leyendo su definición de `lector`, una fila por línea.
+    ///
+    /// Es el parser que usan [`Laberinto::from_path`] y la implementación de [`FromStr`].
+    ///
+    /// # Ejemplo
+    ///
+    /// ```
+    /// use bomberman_r::bomberman::model::laberinto::Laberinto;
+    ///
+    /// let entrada = "B1 _ F1\n_ W _\n";
+    /// let laberinto = Laberinto::from_reader(entrada.as_bytes()).unwrap();
+    /// assert_eq!((laberinto.ancho, laberinto.alto), (3, 2));
+    /// ```
+    pub fn from_reader(lector: impl BufRead) -> Result<Self, LaberintoError> {
         let mut laberinto = Laberinto {
-            tamano: 0,
+            ancho: 0,
+            alto: 0,
             grid: Vec::new(),
         };
 
-        for (fila_index, linea) in lineas.enumerate() {
-            let linea_limpia = match linea {
-                Ok(linea) => Self::eliminar_espacios(linea),
-                Err(err) => return Err(err),
-            };
-            let fila = Self::cargar_laberinto_desde_linea(&linea_limpia, fila_index)?;
+        for (fila_index, linea) in lector.lines().enumerate() {
+            let linea = linea?;
+            let fila = Self::cargar_laberinto_desde_linea(&linea, fila_index)?;
             laberinto.grid.push(fila);
-            laberinto.tamano += 1;
+            laberinto.alto += 1;
         }
 
-        let tamano = laberinto.tamano;
+        // Las líneas vacías al final del archivo no forman parte del tablero
+        while laberinto.grid.last().is_some_and(|fila| fila.is_empty()) {
+            laberinto.grid.pop();
+            laberinto.alto -= 1;
+        }
 
-        if laberinto.grid.iter().all(|fila| fila.len() != tamano) {
-            println!("Error: el tamaño del tablero es incorrecto\n",);
+        if laberinto.grid.is_empty() {
+            return Err(LaberintoError::ArchivoVacio);
         }
 
+        laberinto.ancho = laberinto.grid[0].len();
+        laberinto.validar_tamano()?;
+        laberinto.validar_teletransportadores()?;
+        laberinto.validar_jugadores()?;
+
         Ok(laberinto)
     }
 
-    // Método privado para leer las líneas del archivo
-    fn leer_lineas(path: &str) -> Result<io::Lines<BufReader<File>>, io::Error> {
-        let file = File::open(path).map_err(|e| {
-            io::Error::new(io::ErrorKind::Other, format!("Error al abrir el archivo: {}", e))
-        })?;
-    
-        Ok(io::BufReader::new(file).lines())
+    /// Predice el resultado de detonar la bomba en `(x, y)` sin modificar el laberinto.
+    ///
+    /// Devuelve el laberinto tal como quedaría después de la explosión y un resumen con los
+    /// enemigos eliminados y dañados, las bombas encadenadas y las celdas alcanzadas. Usa
+    /// exactamente la misma lógica que [`detonar_bomba`](crate::bomberman::detonar_bomba).
+    ///
+    /// # Errores
+    ///
+    /// Devuelve un error si `(x, y)` está fuera del laberinto.
+    ///
+    /// # Ejemplo
+    ///
+    /// ```
+    /// use bomberman_r::bomberman::model::laberinto::Laberinto;
+    ///
+    /// let laberinto: Laberinto = "B1 F1\nF2 _".parse().unwrap();
+    /// let (resultado, resumen) = laberinto.predecir_detonacion(0, 0).unwrap();
+    /// assert_eq!(resumen.enemigos_eliminados, 1);
+    /// assert_eq!(resumen.enemigos_daniados, 1);
+    /// assert_eq!(resultado.to_string(), "_ _\nF1 _");
+    /// assert_eq!(laberinto.to_string(), "B1 F1\nF2 _");
+    /// ```
+    pub fn predecir_detonacion(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> Result<(Laberinto, ResumenExplosion), io::Error> {
+        let mut resultado = self.clone();
+        let eventos = detonar_bomba_con_traza(&mut resultado, x, y)?;
+        Ok((resultado, ResumenExplosion::desde_eventos(&eventos)))
+    }
+
+    /// Busca ciclos de desvíos que pueden hacer girar una explosión hasta agotar su alcance.
+    ///
+    /// Conviene llamarla después de cargar el laberinto para advertir sobre estos ciclos antes
+    /// de detonar cualquier bomba. El motor de explosiones los detecta igualmente y detiene la
+    /// explosión al completar una vuelta.
+    ///
+    /// # Ejemplo
+    ///
+    /// ```
+    /// use bomberman_r::bomberman::model::laberinto::Laberinto;
+    ///
+    /// let laberinto: Laberinto = "DR _ DD\n_ _ _\nDU _ DL".parse().unwrap();
+    /// let ciclos = laberinto.ciclos_de_desvio();
+    /// assert_eq!(ciclos.len(), 1);
+    /// assert_eq!(ciclos[0].desvios, vec![(0, 0), (2, 0), (2, 2), (0, 2)]);
+    /// ```
+    pub fn ciclos_de_desvio(&self) -> Vec<CicloDeDesvio> {
+        // 0: sin visitar, 1: en el camino actual, 2: ya procesado
+        let mut estado = vec![vec![0u8; self.ancho]; self.alto];
+        let mut ciclos = Vec::new();
+
+        for fila in &self.grid {
+            for celda in fila {
+                if !matches!(celda.objeto, Objeto::Desvio(_)) || estado[celda.y][celda.x] != 0 {
+                    continue;
+                }
+
+                let mut camino: Vec<((usize, usize), bool)> = Vec::new();
+                let mut actual = Some(((celda.x, celda.y), false));
+                while let Some(((x, y), cruza_roca)) = actual {
+                    match estado[y][x] {
+                        0 => {
+                            estado[y][x] = 1;
+                            camino.push(((x, y), cruza_roca));
+                            actual = self.siguiente_desvio(x, y);
+                        }
+                        1 => {
+                            // Volvimos a un desvío del camino actual: desde él hay un ciclo
+                            let inicio = camino.iter().position(|(p, _)| *p == (x, y));
+                            if let Some(inicio) = inicio {
+                                let desvios = camino[inicio..].iter().map(|(p, _)| *p).collect();
+                                // La roca de la arista que cierra el ciclo también cuenta
+                                let atraviesa_rocas = cruza_roca
+                                    || camino[inicio + 1..].iter().any(|(_, roca)| *roca);
+                                ciclos.push(CicloDeDesvio {
+                                    desvios,
+                                    atraviesa_rocas,
+                                });
+                            }
+                            actual = None;
+                        }
+                        _ => actual = None,
+                    }
+                }
+                for ((x, y), _) in camino {
+                    estado[y][x] = 2;
+                }
+            }
+        }
+
+        ciclos
+    }
+
+    // Método privado que sigue una explosión desviada por el desvío en `(x, y)` y devuelve el
+    // próximo desvío que la vuelve a desviar, indicando si en el camino atraviesa alguna roca.
+    // Como en la explosión, un teletransportador con pareja la hace seguir desde la pareja en
+    // la misma dirección.
+    fn siguiente_desvio(&self, x: usize, y: usize) -> Option<((usize, usize), bool)> {
+        let (dx, dy) = match &self.grid[y][x].objeto {
+            Objeto::Desvio(direccion) => direccion.desplazamiento(),
+            _ => return None,
+        };
+        let mut cruza_roca = false;
+        let (mut x, mut y) = (x, y);
+        loop {
+            x = x.wrapping_add(dx as usize);
+            y = y.wrapping_add(dy as usize);
+            if x >= self.ancho || y >= self.alto {
+                return None;
+            }
+            match self.grid[y][x].objeto {
+                Objeto::Pared | Objeto::Caja(..) => return None,
+                Objeto::Roca => cruza_roca = true,
+                Objeto::Desvio(_) => return Some(((x, y), cruza_roca)),
+                Objeto::Teletransportador(_) => {
+                    if let Some(pareja) = self.pareja_de_teletransportador(x, y) {
+                        (x, y) = pareja;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Escribe el laberinto en `escritor` con el mismo formato que acepta
+    /// [`Laberinto::from_reader`].
+    ///
+    /// Los objetos de cada fila se separan por un espacio y las filas por un salto de línea;
+    /// la última fila no termina en salto de línea.
+    ///
+    /// # Ejemplo
+    ///
+    /// ```
+    /// use bomberman_r::bomberman::model::laberinto::Laberinto;
+    ///
+    /// let laberinto: Laberinto = "B1 _ F1\n_ W DL".parse().unwrap();
+    /// let mut salida = Vec::new();
+    /// laberinto.to_writer(&mut salida).unwrap();
+    /// assert_eq!(salida, b"B1 _ F1\n_ W DL");
+    /// ```
+    pub fn to_writer(&self, mut escritor: impl Write) -> io::Result<()> {
+        write!(escritor, "{}", self)
+    }
+
+    // Método privado que verifica que todas las filas tengan `ancho` columnas
+    fn validar_tamano(&self) -> Result<(), LaberintoError> {
+        let filas_incorrectas: Vec<(usize, usize)> = self
+            .grid
+            .iter()
+            .enumerate()
+            .filter(|(_, fila)| fila.len() != self.ancho)
+            .map(|(fila_index, fila)| (fila_index + 1, fila.len()))
+            .collect();
+
+        if filas_incorrectas.is_empty() {
+            Ok(())
+        } else {
+            Err(LaberintoError::TableroIrregular {
+                columnas: self.ancho,
+                filas_incorrectas,
+            })
+        }
     }
-    
 
-    fn parsear_entero(iter: &mut std::iter::Peekable<std::str::Chars>, default: i32) -> i32 {
+    // Método privado que verifica que cada teletransportador tenga exactamente una pareja.
+    // Si hay varios incorrectos se informa el de menor número.
+    fn validar_teletransportadores(&self) -> Result<(), LaberintoError> {
+        let mut posiciones: BTreeMap<u8, Vec<(usize, usize)>> = BTreeMap::new();
+        for celda in self.grid.iter().flatten() {
+            if let Objeto::Teletransportador(id) = celda.objeto {
+                posiciones.entry(id).or_default().push((celda.x, celda.y));
+            }
+        }
+
+        match posiciones.into_iter().find(|(_, lista)| lista.len() != 2) {
+            Some((id, posiciones)) => {
+                Err(LaberintoError::TeletransportadorSinPareja { id, posiciones })
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Método privado que verifica que no haya dos jugadores con el mismo número. Si hay
+    // varios repetidos se informa la primera repetición en orden de lectura.
+    fn validar_jugadores(&self) -> Result<(), LaberintoError> {
+        let mut vistos = HashSet::new();
+        for celda in self.grid.iter().flatten() {
+            if let Objeto::Jugador(id, _) = celda.objeto {
+                if !vistos.insert(id) {
+                    return Err(LaberintoError::JugadorRepetido {
+                        id,
+                        linea: celda.y + 1,
+                        columna: celda.x + 1,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Devuelve la pareja del teletransportador en `(x, y)`, o `None` si en `(x, y)` no hay un
+    /// teletransportador o si no tiene pareja.
+    pub fn pareja_de_teletransportador(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        let Objeto::Teletransportador(id) = self.grid.get(y)?.get(x)?.objeto else {
+            return None;
+        };
+        self.grid
+            .iter()
+            .flatten()
+            .find(|celda| {
+                celda.objeto == Objeto::Teletransportador(id) && (celda.x, celda.y) != (x, y)
+            })
+            .map(|celda| (celda.x, celda.y))
+    }
+
+    // Método privado que parsea los dígitos consecutivos. Si no hay dígitos se usa `default`,
+    // y si no entran en un `i32` se devuelven los dígitos leídos como error.
+    fn parsear_entero<I: Iterator<Item = (usize, char)>>(
+        iter: &mut Peekable<I>,
+        default: i32,
+    ) -> Result<i32, String> {
         let mut valor = String::new();
-        while let Some(&next_char) = iter.peek() {
+        while let Some(&(_, next_char)) = iter.peek() {
             if next_char.is_ascii_digit() {
                 valor.push(next_char);
                 iter.next(); // Avanzar el iterador para consumir el dígito
@@ -94,31 +350,84 @@ impl Laberinto {
             }
         }
 
-        match valor.parse::<i32>() {
-            Ok(parsed_value) => parsed_value,
-            Err(_) => default,
+        if valor.is_empty() {
+            return Ok(default);
+        }
+
+        valor.parse::<i32>().map_err(|_| valor)
+    }
+
+    // Método privado que carga el objeto que sigue al '+' de un objeto que contiene a otro,
+    // cuyo texto hasta el '+' es `prefijo`. Sólo se aceptan los objetos cuyo carácter cumple
+    // `permitido`; los errores incluyen el prefijo.
+    fn cargar_objeto_interior<I: Iterator<Item = (usize, char)>>(
+        prefijo: &str,
+        iter: &mut Peekable<I>,
+        permitido: impl Fn(char) -> bool,
+    ) -> Result<Objeto, ErrorDeObjeto> {
+        match iter.next() {
+            Some((_, c)) if permitido(c) => {
+                Self::cargar_objeto(c, iter).map_err(|error| error.con_prefijo(prefijo))
+            }
+            Some((_, c)) => Err(ErrorDeObjeto::CaracterDesconocido(format!(
+                "{}{}",
+                prefijo, c
+            ))),
+            None => Err(ErrorDeObjeto::CaracterDesconocido(prefijo.to_string())),
+        }
+    }
+
+    // Método privado que parsea el sufijo opcional con el patrón de una bomba. Sin sufijo la
+    // bomba explota en cruz.
+    fn parsear_patron<I: Iterator<Item = (usize, char)>>(iter: &mut Peekable<I>) -> Patron {
+        let patron = match iter.peek() {
+            Some((_, 'X')) => Patron::Equis,
+            Some((_, '*')) => Patron::Estrella,
+            Some((_, '#')) => Patron::Cuadrado,
+            _ => return Patron::Cruz,
+        };
+        iter.next(); // Consumir el sufijo
+        patron
+    }
+
+    // Método privado que parsea la mecha opcional de una bomba, escrita como `:` seguido de la
+    // cantidad de turnos. Si los dígitos faltan o no entran en un `i32` se devuelve el texto
+    // leído como error.
+    fn parsear_mecha<I: Iterator<Item = (usize, char)>>(
+        iter: &mut Peekable<I>,
+    ) -> Result<Option<i32>, String> {
+        if !matches!(iter.peek(), Some((_, ':'))) {
+            return Ok(None);
+        }
+        iter.next(); // Consumir el ':'
+        match Self::parsear_entero(iter, -1) {
+            Ok(-1) => Err(String::from(":")),
+            Ok(mecha) => Ok(Some(mecha)),
+            Err(digitos) => Err(format!(":{}", digitos)),
         }
     }
 
-    // Método privado para eliminar espacios de una cadena
-    fn eliminar_espacios(linea: String) -> String {
-        linea.chars().filter(|c| !c.is_whitespace()).collect()
+    // Método privado que recorre los caracteres de una línea ignorando los espacios, junto con
+    // la columna (comenzando en 0) que ocupa cada uno en la línea original.
+    fn caracteres_sin_espacios(linea: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+        linea
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace())
     }
 
     // Método privado para cargar un laberinto desde una línea de texto
     fn cargar_laberinto_desde_linea(
         linea: &str,
         fila_index: usize,
-    ) -> Result<Vec<Celda>, io::Error> {
+    ) -> Result<Vec<Celda>, LaberintoError> {
         let mut fila = Vec::new();
-        let mut iter = linea.chars().peekable();
+        let mut iter = Self::caracteres_sin_espacios(linea).peekable();
         let mut col_index = 0; // Contador de columna
 
-        while let Some(caracter) = iter.next() {
-            let objeto = match Self::cargar_objeto(caracter, &mut iter) {
-                Ok(obj) => obj,
-                Err(err) => return Err(err),
-            };
+        while let Some((columna, caracter)) = iter.next() {
+            let objeto = Self::cargar_objeto(caracter, &mut iter)
+                .map_err(|error| error.en(fila_index + 1, columna + 1))?;
 
             fila.push(Celda {
                 objeto,
@@ -131,54 +440,210 @@ impl Laberinto {
         Ok(fila)
     }
 
-    fn parsear_direccion(
-        iter: &mut std::iter::Peekable<std::str::Chars>,
+    // Método privado que parsea la dirección de un desvío. Si la línea termina se usa
+    // `default`, y si el carácter no es una dirección se lo devuelve como error.
+    fn parsear_direccion<I: Iterator<Item = (usize, char)>>(
+        iter: &mut Peekable<I>,
         default: Direccion,
-    ) -> Direccion {
-        if let Some(&next_char) = iter.peek() {
-            let direccion = match next_char {
-                'L' => Direccion::Izquierda,
-                'R' => Direccion::Derecha,
-                'U' => Direccion::Arriba,
-                'D' => Direccion::Abajo,
-                _ => default,
-            };
-            iter.next(); // Avanzar el iterador para consumir la dirección
-            direccion
+    ) -> Result<Direccion, String> {
+        if let Some((_, next_char)) = iter.next() {
+            match next_char {
+                'L' => Ok(Direccion::Izquierda),
+                'R' => Ok(Direccion::Derecha),
+                'U' => Ok(Direccion::Arriba),
+                'D' => Ok(Direccion::Abajo),
+                // Las diagonales se escriben con dos letras: N o S y después E o W
+                'N' | 'S' => match (next_char, iter.next()) {
+                    ('N', Some((_, 'E'))) => Ok(Direccion::NorEste),
+                    ('N', Some((_, 'W'))) => Ok(Direccion::NorOeste),
+                    ('S', Some((_, 'E'))) => Ok(Direccion::SurEste),
+                    ('S', Some((_, 'W'))) => Ok(Direccion::SurOeste),
+                    (_, Some((_, otro))) => Err(format!("{}{}", next_char, otro)),
+                    (_, None) => Err(next_char.to_string()),
+                },
+                _ => Err(next_char.to_string()),
+            }
         } else {
-            default
+            Ok(default)
         }
     }
 
     // Método privado para cargar un objeto desde un carácter
-    fn cargar_objeto(
+    fn cargar_objeto<I: Iterator<Item = (usize, char)>>(
         c: char,
-        iter: &mut std::iter::Peekable<std::str::Chars>,
-    ) -> Result<Objeto, io::Error> {
+        iter: &mut Peekable<I>,
+    ) -> Result<Objeto, ErrorDeObjeto> {
+        let numero_invalido = |digitos| ErrorDeObjeto::NumeroInvalido(format!("{}{}", c, digitos));
         match c {
             'F' => {
-                let puntos_vida = Self::parsear_entero(iter, 1);
+                let puntos_vida = Self::parsear_entero(iter, 1).map_err(numero_invalido)?;
                 Ok(Objeto::Enemigo(puntos_vida))
             }
-            'B' => {
-                let alcance = Self::parsear_entero(iter, 0);
-                Ok(Objeto::Bomba(alcance))
-            }
-            'S' => {
-                let alcance = Self::parsear_entero(iter, 0);
-                Ok(Objeto::BombaTraspaso(alcance))
+            'B' | 'S' => {
+                let alcance = Self::parsear_entero(iter, 0).map_err(numero_invalido)?;
+                let patron = Self::parsear_patron(iter);
+                let mecha = Self::parsear_mecha(iter)
+                    .map_err(|resto| numero_invalido(format!("{}{}{}", alcance, patron, resto)))?;
+                Ok(Objeto::bomba(alcance, patron, c == 'S', mecha))
             }
             'R' => Ok(Objeto::Roca),
             'W' => Ok(Objeto::Pared),
             'D' => {
-                let direccion = Self::parsear_direccion(iter, Direccion::Arriba);
+                let direccion = Self::parsear_direccion(iter, Direccion::Arriba)
+                    .map_err(|d| ErrorDeObjeto::DireccionInvalida(format!("D{}", d)))?;
                 Ok(Objeto::Desvio(direccion))
             }
             '_' => Ok(Objeto::Vacio),
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Carácter desconocido",
-            )),
+            'T' => match Self::parsear_entero(iter, -1) {
+                Ok(id @ 1..=9) => Ok(Objeto::Teletransportador(id as u8)),
+                Ok(-1) => Err(ErrorDeObjeto::NumeroInvalido(String::from("T"))),
+                Ok(id) => Err(numero_invalido(id.to_string())),
+                Err(digitos) => Err(numero_invalido(digitos)),
+            },
+            'M' => match iter.next() {
+                Some((_, 'A')) => Ok(Objeto::Mejora(Mejora::Alcance)),
+                Some((_, 'T')) => Ok(Objeto::Mejora(Mejora::Traspaso)),
+                Some((_, 'B')) => Ok(Objeto::Mejora(Mejora::BombaExtra)),
+                Some((_, otro)) => Err(ErrorDeObjeto::MejoraInvalida(format!("M{}", otro))),
+                None => Err(ErrorDeObjeto::MejoraInvalida(String::from("M"))),
+            },
+            'P' => {
+                let id = Self::parsear_entero(iter, 1).map_err(numero_invalido)? as u32;
+                if !matches!(iter.peek(), Some((_, '+'))) {
+                    return Ok(Objeto::Jugador(id, None));
+                }
+                iter.next(); // Consumir el '+'
+
+                // Sólo puede haber una bomba debajo del jugador
+                let bomba = Self::cargar_objeto_interior(&format!("P{}+", id), iter, |c| {
+                    c == 'B' || c == 'S'
+                })?;
+                Ok(Objeto::Jugador(id, Some(Box::new(bomba))))
+            }
+            'C' => {
+                // Una caja con resistencia 0 no podría romperse nunca
+                let resistencia = match Self::parsear_entero(iter, 1) {
+                    Ok(0) => Err(String::from("0")),
+                    resultado => resultado,
+                }
+                .map_err(numero_invalido)?;
+                if !matches!(iter.peek(), Some((_, '+'))) {
+                    return Ok(Objeto::Caja(resistencia, None));
+                }
+                iter.next(); // Consumir el '+'
+
+                // La caja puede esconder cualquier objeto salvo otra caja, un jugador o un
+                // teletransportador, que tiene que estar a la vista para formar pareja
+                let contenido =
+                    Self::cargar_objeto_interior(&format!("C{}+", resistencia), iter, |c| {
+                        c != 'C' && c != 'P' && c != 'T'
+                    })?;
+                Ok(Objeto::Caja(resistencia, Some(Box::new(contenido))))
+            }
+            _ => Err(ErrorDeObjeto::CaracterDesconocido(c.to_string())),
+        }
+    }
+}
+
+/// Ciclo de desvíos en el que una explosión puede girar hasta agotar su alcance.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CicloDeDesvio {
+    /// Posiciones `(x, y)` de los desvíos, en el orden en que los recorre la explosión.
+    pub desvios: Vec<(usize, usize)>,
+    /// Indica si el ciclo atraviesa rocas, en cuyo caso sólo lo recorren las bombas de
+    /// traspaso.
+    pub atraviesa_rocas: bool,
+}
+
+impl fmt::Display for CicloDeDesvio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ciclo de desvíos")?;
+        for (x, y) in &self.desvios {
+            write!(f, " ({}, {})", x, y)?;
+        }
+        if self.atraviesa_rocas {
+            write!(f, " (sólo con bombas de traspaso)")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Laberinto {
+    type Err = LaberintoError;
+
+    /// Carga un laberinto desde el texto `s`, con el mismo formato que los archivos.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_reader(s.as_bytes())
+    }
+}
+
+impl fmt::Display for Laberinto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (fila_index, fila) in self.grid.iter().enumerate() {
+            if fila_index > 0 {
+                writeln!(f)?; // Nueva línea para la siguiente fila
+            }
+            for (col_index, celda) in fila.iter().enumerate() {
+                if col_index > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", celda.objeto)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Error de un objeto individual, antes de conocer su posición en el archivo.
+#[derive(Debug, PartialEq)]
+enum ErrorDeObjeto {
+    CaracterDesconocido(String),
+    NumeroInvalido(String),
+    DireccionInvalida(String),
+    MejoraInvalida(String),
+}
+
+impl ErrorDeObjeto {
+    // Agrega `prefijo` al texto del error, para los objetos que forman parte de otro
+    fn con_prefijo(self, prefijo: &str) -> Self {
+        match self {
+            ErrorDeObjeto::CaracterDesconocido(token) => {
+                ErrorDeObjeto::CaracterDesconocido(format!("{}{}", prefijo, token))
+            }
+            ErrorDeObjeto::NumeroInvalido(token) => {
+                ErrorDeObjeto::NumeroInvalido(format!("{}{}", prefijo, token))
+            }
+            ErrorDeObjeto::DireccionInvalida(token) => {
+                ErrorDeObjeto::DireccionInvalida(format!("{}{}", prefijo, token))
+            }
+            ErrorDeObjeto::MejoraInvalida(token) => {
+                ErrorDeObjeto::MejoraInvalida(format!("{}{}", prefijo, token))
+            }
+        }
+    }
+
+    fn en(self, linea: usize, columna: usize) -> LaberintoError {
+        match self {
+            ErrorDeObjeto::CaracterDesconocido(token) => LaberintoError::CaracterDesconocido {
+                linea,
+                columna,
+                token,
+            },
+            ErrorDeObjeto::NumeroInvalido(token) => LaberintoError::NumeroInvalido {
+                linea,
+                columna,
+                token,
+            },
+            ErrorDeObjeto::DireccionInvalida(token) => LaberintoError::DireccionInvalida {
+                linea,
+                columna,
+                token,
+            },
+            ErrorDeObjeto::MejoraInvalida(token) => LaberintoError::MejoraInvalida {
+                linea,
+                columna,
+                token,
+            },
         }
     }
 }
@@ -187,10 +652,16 @@ impl Laberinto {
 mod tests {
     use super::*;
 
+    fn caracteres(texto: &str) -> Peekable<impl Iterator<Item = (usize, char)> + '_> {
+        Laberinto::caracteres_sin_espacios(texto).peekable()
+    }
+
     #[test]
     fn test_eliminar_espacios() {
         let linea = String::from("A B C1 D E2");
-        let linea_limpia = Laberinto::eliminar_espacios(linea);
+        let linea_limpia: String = Laberinto::caracteres_sin_espacios(&linea)
+            .map(|(_, c)| c)
+            .collect();
         assert_eq!(linea_limpia, "ABC1DE2");
     }
 
@@ -198,34 +669,481 @@ mod tests {
     fn test_parsear_entero() {
         // Prueba para parsear enteros de una cadena
         assert_eq!(
-            Laberinto::parsear_entero(&mut "123".chars().peekable(), 0),
-            123
+            Laberinto::parsear_entero(&mut caracteres("123"), 0),
+            Ok(123)
+        );
+        assert_eq!(
+            Laberinto::parsear_entero(&mut caracteres("42abc"), 0),
+            Ok(42)
+        );
+        assert_eq!(
+            Laberinto::parsear_entero(&mut caracteres("abc"), 10),
+            Ok(10)
+        ); // El valor por defecto se usa si no hay dígitos
+        assert_eq!(
+            Laberinto::parsear_entero(&mut caracteres("99999999999"), 0),
+            Err(String::from("99999999999"))
+        );
+    }
+
+    #[test]
+    fn test_parsear_mecha() {
+        assert_eq!(
+            Laberinto::parsear_mecha(&mut caracteres(":3 F1")),
+            Ok(Some(3))
+        );
+        assert_eq!(Laberinto::parsear_mecha(&mut caracteres(" F1")), Ok(None));
+        assert_eq!(
+            Laberinto::parsear_mecha(&mut caracteres(": F1")),
+            Err(String::from(":"))
+        );
+
+        let laberinto: Laberinto = "B2:3 S1:0 B4".parse().unwrap();
+        assert_eq!(laberinto.grid[0][0].objeto, Objeto::BombaTemporizada(2, 3));
+        assert_eq!(
+            laberinto.grid[0][1].objeto,
+            Objeto::BombaTraspasoTemporizada(1, 0)
+        );
+        assert_eq!(laberinto.grid[0][2].objeto, Objeto::Bomba(4));
+    }
+
+    #[test]
+    fn test_cargar_bombas_con_patron() {
+        let laberinto: Laberinto = "B2X S3* B1#:4 DNE DSW B2".parse().unwrap();
+        assert_eq!(
+            laberinto.grid[0][0].objeto,
+            Objeto::BombaConPatron {
+                alcance: 2,
+                patron: Patron::Equis,
+                traspaso: false,
+                mecha: None
+            }
+        );
+        assert_eq!(
+            laberinto.grid[0][1].objeto,
+            Objeto::BombaConPatron {
+                alcance: 3,
+                patron: Patron::Estrella,
+                traspaso: true,
+                mecha: None
+            }
+        );
+        assert_eq!(laberinto.grid[0][2].objeto.patron(), Some(Patron::Cuadrado));
+        assert_eq!(laberinto.grid[0][2].objeto.mecha(), Some(4));
+        assert_eq!(
+            laberinto.grid[0][3].objeto,
+            Objeto::Desvio(Direccion::NorEste)
+        );
+        assert_eq!(
+            laberinto.grid[0][4].objeto,
+            Objeto::Desvio(Direccion::SurOeste)
+        );
+        assert_eq!(laberinto.grid[0][5].objeto, Objeto::Bomba(2));
+        assert_eq!(laberinto.to_string(), "B2X S3* B1#:4 DNE DSW B2");
+        assert_eq!(
+            Objeto::bomba(2, Patron::Cruz, true, Some(1)),
+            Objeto::BombaTraspasoTemporizada(2, 1)
+        );
+
+        let error = Laberinto::cargar_laberinto_desde_linea("B2X:", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::NumeroInvalido { ref token, .. } if token == "B2X:"
+        ));
+    }
+
+    #[test]
+    fn test_cargar_cajas() {
+        let laberinto: Laberinto = "C C3 C2+B1:2".parse().unwrap();
+        assert_eq!(laberinto.grid[0][0].objeto, Objeto::Caja(1, None));
+        assert_eq!(laberinto.grid[0][1].objeto, Objeto::Caja(3, None));
+        assert_eq!(
+            laberinto.grid[0][2].objeto,
+            Objeto::Caja(2, Some(Box::new(Objeto::BombaTemporizada(1, 2))))
+        );
+
+        let error = Laberinto::cargar_laberinto_desde_linea("C0", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::NumeroInvalido { ref token, .. } if token == "C0"
+        ));
+        let error = Laberinto::cargar_laberinto_desde_linea("C1+C1", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::CaracterDesconocido { ref token, .. } if token == "C1+C"
+        ));
+        let error = "B1 C1+T1 T1 _ T1".parse::<Laberinto>().unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::CaracterDesconocido { columna: 4, ref token, .. } if token == "C1+T"
+        ));
+        let error = Laberinto::cargar_laberinto_desde_linea("C1+DX", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::DireccionInvalida { ref token, .. } if token == "C1+DX"
+        ));
+    }
+
+    #[test]
+    fn test_cargar_mejoras() {
+        let laberinto: Laberinto = "MA MT MB C2+MA".parse().unwrap();
+        assert_eq!(laberinto.grid[0][0].objeto, Objeto::Mejora(Mejora::Alcance));
+        assert_eq!(
+            laberinto.grid[0][1].objeto,
+            Objeto::Mejora(Mejora::Traspaso)
+        );
+        assert_eq!(
+            laberinto.grid[0][2].objeto,
+            Objeto::Mejora(Mejora::BombaExtra)
+        );
+        assert_eq!(
+            laberinto.grid[0][3].objeto,
+            Objeto::Caja(2, Some(Box::new(Objeto::Mejora(Mejora::Alcance))))
         );
+
+        let error = Laberinto::cargar_laberinto_desde_linea("_ MX", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::MejoraInvalida { linea: 1, columna: 3, ref token } if token == "MX"
+        ));
+        let error = Laberinto::cargar_laberinto_desde_linea("C1+M", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::MejoraInvalida { ref token, .. } if token == "C1+M"
+        ));
+    }
+
+    #[test]
+    fn test_teletransportadores() {
+        let laberinto: Laberinto = "T1 _ T2\nT2 _ T1".parse().unwrap();
+        assert_eq!(laberinto.grid[0][0].objeto, Objeto::Teletransportador(1));
+        assert_eq!(laberinto.pareja_de_teletransportador(0, 0), Some((2, 1)));
+        assert_eq!(laberinto.pareja_de_teletransportador(0, 1), Some((2, 0)));
+        assert_eq!(laberinto.pareja_de_teletransportador(1, 0), None);
+
+        for (token, invalido) in [("T", "T"), ("T0", "T0"), ("T12", "T12")] {
+            let error = Laberinto::cargar_laberinto_desde_linea(token, 0).unwrap_err();
+            assert!(matches!(
+                error,
+                LaberintoError::NumeroInvalido { ref token, .. } if token == invalido
+            ));
+        }
+    }
+
+    #[test]
+    fn test_teletransportadores_sin_pareja() {
+        let error = "T3 T1 T3\n_ T1 T3".parse::<Laberinto>().unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::TeletransportadorSinPareja { id: 3, ref posiciones }
+                if *posiciones == vec![(0, 0), (2, 0), (2, 1)]
+        ));
         assert_eq!(
-            Laberinto::parsear_entero(&mut "42abc".chars().peekable(), 0),
-            42
+            error.to_string(),
+            "el teletransportador T3 debe aparecer exactamente dos veces, pero aparece 3: \
+             (0, 0), (2, 0), (2, 1)"
         );
+
+        assert!(matches!(
+            "T5 _".parse::<Laberinto>(),
+            Err(LaberintoError::TeletransportadorSinPareja { id: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn test_jugadores_repetidos() {
+        let error = "P1 _ P2
+P2 _ P1"
+            .parse::<Laberinto>()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::JugadorRepetido {
+                id: 2,
+                linea: 2,
+                columna: 1
+            }
+        ));
         assert_eq!(
-            Laberinto::parsear_entero(&mut "abc".chars().peekable(), 10),
-            10
-        ); // El valor por defecto se usa en caso de error
+            error.to_string(),
+            "línea 2, columna 1: el jugador P2 aparece más de una vez"
+        );
+
+        // `P` es el jugador 1
+        assert!(matches!(
+            "P1 _ P+B1".parse::<Laberinto>(),
+            Err(LaberintoError::JugadorRepetido { id: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_cargar_jugadores() {
+        let laberinto: Laberinto = "P3 P2+S3:2 P".parse().unwrap();
+        assert_eq!(laberinto.grid[0][0].objeto, Objeto::Jugador(3, None));
+        assert_eq!(
+            laberinto.grid[0][1].objeto,
+            Objeto::Jugador(2, Some(Box::new(Objeto::BombaTraspasoTemporizada(3, 2))))
+        );
+        assert_eq!(laberinto.grid[0][2].objeto, Objeto::Jugador(1, None));
+
+        let error = Laberinto::cargar_laberinto_desde_linea("_ P1+W", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::CaracterDesconocido { linea: 1, columna: 3, ref token } if token == "P1+W"
+        ));
+
+        let error = Laberinto::cargar_laberinto_desde_linea("P1+B2:", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::NumeroInvalido { linea: 1, columna: 1, ref token } if token == "P1+B2:"
+        ));
     }
 
     #[test]
     fn test_parsear_direccion() {
         // Prueba para parsear direcciones de una cadena
         assert_eq!(
-            Laberinto::parsear_direccion(&mut "LRU".chars().peekable(), Direccion::Abajo),
-            Direccion::Izquierda
+            Laberinto::parsear_direccion(&mut caracteres("LRU"), Direccion::Abajo),
+            Ok(Direccion::Izquierda)
+        );
+        assert_eq!(
+            Laberinto::parsear_direccion(&mut caracteres("R"), Direccion::Arriba),
+            Ok(Direccion::Derecha)
+        );
+        assert_eq!(
+            Laberinto::parsear_direccion(&mut caracteres(""), Direccion::Izquierda),
+            Ok(Direccion::Izquierda)
+        );
+        assert_eq!(
+            Laberinto::parsear_direccion(&mut caracteres("XYZ"), Direccion::Izquierda),
+            Err(String::from("X"))
+        );
+        assert_eq!(
+            Laberinto::parsear_direccion(&mut caracteres("NE"), Direccion::Arriba),
+            Ok(Direccion::NorEste)
+        );
+        assert_eq!(
+            Laberinto::parsear_direccion(&mut caracteres("SW"), Direccion::Arriba),
+            Ok(Direccion::SurOeste)
         );
         assert_eq!(
-            Laberinto::parsear_direccion(&mut "R".chars().peekable(), Direccion::Arriba),
-            Direccion::Derecha
+            Laberinto::parsear_direccion(&mut caracteres("NU"), Direccion::Arriba),
+            Err(String::from("NU"))
         );
+    }
+
+    fn laberinto_desde_lineas(lineas: &[&str]) -> Laberinto {
+        let grid: Vec<Vec<Celda>> = lineas
+            .iter()
+            .enumerate()
+            .map(|(fila_index, linea)| {
+                Laberinto::cargar_laberinto_desde_linea(linea, fila_index).unwrap()
+            })
+            .collect();
+        Laberinto {
+            ancho: grid[0].len(),
+            alto: lineas.len(),
+            grid,
+        }
+    }
+
+    #[test]
+    fn test_validar_tamano() {
+        let laberinto = laberinto_desde_lineas(&["_ _ _", "_ W _", "_ _ _"]);
+        assert!(laberinto.validar_tamano().is_ok());
+
+        // Un tablero rectangular es válido
+        let laberinto = laberinto_desde_lineas(&["_ _ _ _ _", "_ W _ W _"]);
+        assert!(laberinto.validar_tamano().is_ok());
+        assert_eq!((laberinto.ancho, laberinto.alto), (5, 2));
+
+        let laberinto = laberinto_desde_lineas(&["_ _ _", "_ W", "_ _ _", "_ _ _ _"]);
+        assert!(matches!(
+            laberinto.validar_tamano(),
+            Err(LaberintoError::TableroIrregular { columnas: 3, ref filas_incorrectas })
+                if filas_incorrectas == &vec![(2, 2), (4, 4)]
+        ));
+    }
+
+    #[test]
+    fn test_from_str() {
+        let laberinto: Laberinto = "B2 _ DL\n_ W _\n\n".parse().unwrap();
+        assert_eq!((laberinto.ancho, laberinto.alto), (3, 2));
+        assert_eq!(laberinto.grid[0][0].objeto, Objeto::Bomba(2));
         assert_eq!(
-            Laberinto::parsear_direccion(&mut "XYZ".chars().peekable(), Direccion::Izquierda),
-            Direccion::Izquierda
+            laberinto.grid[0][2].objeto,
+            Objeto::Desvio(Direccion::Izquierda)
         );
+
+        assert!(matches!(
+            "".parse::<Laberinto>(),
+            Err(LaberintoError::ArchivoVacio)
+        ));
     }
 
+    // Generador pseudoaleatorio simple (xorshift) para las pruebas de propiedades
+    struct Generador(u64);
+
+    impl Generador {
+        fn siguiente(&mut self, limite: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % limite
+        }
+
+        fn objeto(&mut self) -> Objeto {
+            let numero = self.siguiente(20) as i32;
+            match self.siguiente(17) {
+                0 => Objeto::Enemigo(numero),
+                1 => Objeto::Bomba(numero),
+                2 => Objeto::BombaTraspaso(numero),
+                7 => Objeto::BombaTemporizada(numero, self.siguiente(5) as i32),
+                8 => Objeto::BombaTraspasoTemporizada(numero, self.siguiente(5) as i32),
+                9 => Objeto::Jugador(numero as u32, None),
+                11 => Objeto::Caja(numero + 1, None),
+                12 => Objeto::Caja(numero + 1, Some(Box::new(Objeto::Enemigo(numero)))),
+                13 => Objeto::Mejora(Mejora::Alcance),
+                14 => Objeto::Mejora(Mejora::Traspaso),
+                15 => Objeto::Caja(1, Some(Box::new(Objeto::Mejora(Mejora::BombaExtra)))),
+                10 => Objeto::Jugador(
+                    numero as u32,
+                    Some(Box::new(Objeto::BombaTemporizada(numero, 1))),
+                ),
+                3 => Objeto::Roca,
+                4 => Objeto::Pared,
+                5 => Objeto::Desvio(Direccion::TODAS[self.siguiente(8) as usize].clone()),
+                16 => Objeto::bomba(
+                    numero,
+                    match self.siguiente(4) {
+                        0 => Patron::Cruz,
+                        1 => Patron::Equis,
+                        2 => Patron::Estrella,
+                        _ => Patron::Cuadrado,
+                    },
+                    self.siguiente(2) == 0,
+                    (self.siguiente(2) == 0).then_some(numero),
+                ),
+                _ => Objeto::Vacio,
+            }
+        }
+
+        fn laberinto(&mut self) -> Laberinto {
+            let ancho = 1 + self.siguiente(12) as usize;
+            let alto = 1 + self.siguiente(12) as usize;
+            let grid = (0..alto)
+                .map(|y| {
+                    (0..ancho)
+                        .map(|x| Celda {
+                            objeto: self.objeto(),
+                            x,
+                            y,
+                        })
+                        .collect()
+                })
+                .collect();
+            let mut laberinto = Laberinto { ancho, alto, grid };
+
+            // Cada jugador tiene un número distinto
+            let mut numero = 0;
+            for celda in laberinto.grid.iter_mut().flatten() {
+                if let Objeto::Jugador(id, _) = &mut celda.objeto {
+                    numero += 1;
+                    *id = numero;
+                }
+            }
+
+            // Los teletransportadores tienen que aparecer de a dos
+            let celdas = ancho * alto;
+            if celdas >= 2 {
+                let id = 1 + self.siguiente(9) as u8;
+                let primera = self.siguiente(celdas as u64) as usize;
+                let segunda = (primera + 1 + self.siguiente(celdas as u64 - 1) as usize) % celdas;
+                for indice in [primera, segunda] {
+                    laberinto.grid[indice / ancho][indice % ancho].objeto =
+                        Objeto::Teletransportador(id);
+                }
+            }
+            laberinto
+        }
+    }
+
+    #[test]
+    fn test_display_ida_y_vuelta() {
+        let mut generador = Generador(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let laberinto = generador.laberinto();
+            let texto = laberinto.to_string();
+            let leido: Laberinto = texto.parse().unwrap();
+            assert_eq!(leido, laberinto);
+            assert_eq!(leido.to_string(), texto);
+        }
+    }
+
+    #[test]
+    fn test_display_ejemplo() {
+        let texto = "B2 R R _ F1 _ _\n_ W R W _ W _\nS4 _ DU _ B2 DL _";
+        let laberinto: Laberinto = texto.parse().unwrap();
+        assert_eq!(laberinto.to_string(), texto);
+    }
+
+    #[test]
+    fn test_ciclos_de_desvio() {
+        let laberinto = laberinto_desde_lineas(&["DR R DD", "_ _ _", "DU _ DL"]);
+        assert_eq!(
+            laberinto.ciclos_de_desvio(),
+            vec![CicloDeDesvio {
+                desvios: vec![(0, 0), (2, 0), (2, 2), (0, 2)],
+                atraviesa_rocas: true,
+            }]
+        );
+
+        // Una pared corta el ciclo; el desvío que apunta hacia él no forma parte de ninguno
+        let laberinto = laberinto_desde_lineas(&["DR _ DD _", "_ _ W _", "DU _ DL DL"]);
+        assert!(laberinto.ciclos_de_desvio().is_empty());
+
+        let laberinto = laberinto_desde_lineas(&["DR _ DD _", "_ _ _ _", "DU _ DL DL"]);
+        assert_eq!(laberinto.ciclos_de_desvio().len(), 1);
+        assert!(!laberinto.ciclos_de_desvio()[0].atraviesa_rocas);
+
+        // La explosión que entra a un teletransportador sigue desde su pareja
+        let laberinto = laberinto_desde_lineas(&["DR T1 DL", "_ _ _", "B1 T1 _"]);
+        assert!(laberinto.ciclos_de_desvio().is_empty());
+
+        let laberinto = laberinto_desde_lineas(&["DR T1 DL", "_ _ _", "DU T1 DU"]);
+        assert_eq!(
+            laberinto.ciclos_de_desvio(),
+            vec![CicloDeDesvio {
+                desvios: vec![(0, 0), (2, 2), (2, 0), (0, 2)],
+                atraviesa_rocas: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_error_indica_linea_y_columna() {
+        let error = Laberinto::cargar_laberinto_desde_linea("B5 _ K _ B2 _ _", 2).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::CaracterDesconocido { linea: 3, columna: 6, ref token } if token == "K"
+        ));
+
+        let error = Laberinto::cargar_laberinto_desde_linea("_ DX _", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::DireccionInvalida { linea: 1, columna: 3, ref token } if token == "DX"
+        ));
+
+        let error = Laberinto::cargar_laberinto_desde_linea("F99999999999", 4).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::NumeroInvalido { linea: 5, columna: 1, ref token } if token == "F99999999999"
+        ));
+
+        let error = Laberinto::cargar_laberinto_desde_linea("_ B3: _", 0).unwrap_err();
+        assert!(matches!(
+            error,
+            LaberintoError::NumeroInvalido { linea: 1, columna: 3, ref token } if token == "B3:"
+        ));
+    }
 }
@@ -0,0 +1,26 @@
+use std::fmt;
+
+/// Mejoras que un jugador puede recoger al entrar en su celda.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Mejora {
+    /// Aumenta en 1 el alcance de las bombas del jugador.
+    Alcance,
+
+    /// Convierte las bombas del jugador en bombas de traspaso.
+    Traspaso,
+
+    /// Permite al jugador tener una bomba más colocada a la vez.
+    BombaExtra,
+}
+
+impl fmt::Display for Mejora {
+    /// Escribe la letra con la que se representa la mejora en los archivos de laberinto.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letra = match self {
+            Mejora::Alcance => "A",
+            Mejora::Traspaso => "T",
+            Mejora::BombaExtra => "B",
+        };
+        write!(f, "{}", letra)
+    }
+}
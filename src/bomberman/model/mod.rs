@@ -1,4 +1,8 @@
 pub mod celda;
 pub mod direccion;
+pub mod error;
+pub mod evento;
 pub mod laberinto;
+pub mod mejora;
 pub mod objeto;
+pub mod patron;
@@ -1,5 +1,9 @@
+use std::fmt;
+
+use super::patron::Patron;
+
 /// Enumeración que define los diferentes tipos de objetos en el laberinto.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Objeto {
     /// Representa un enemigo con una cantidad específica de vidas.
     Enemigo(i32),
@@ -10,15 +14,160 @@ pub enum Objeto {
     /// Representa una bomba traspasable con un alcance específico.
     BombaTraspaso(i32),
 
+    /// Representa una bomba con un alcance y una mecha: la cantidad de turnos que faltan para
+    /// que explote sola.
+    BombaTemporizada(i32, i32),
+
+    /// Representa una bomba traspasable con un alcance y una mecha.
+    BombaTraspasoTemporizada(i32, i32),
+
+    /// Representa una bomba cuya explosión sigue un patrón configurable. Puede ser de
+    /// traspaso y tener mecha, como las demás bombas. Las bombas en cruz usan las variantes
+    /// sin patrón; [`Objeto::bomba`] elige la variante que corresponde.
+    BombaConPatron {
+        alcance: i32,
+        patron: Patron,
+        traspaso: bool,
+        mecha: Option<i32>,
+    },
+
     /// Representa una roca en el laberinto.
     Roca,
 
+    /// Representa una caja destructible con una resistencia: la cantidad de explosiones que
+    /// soporta antes de romperse. Puede esconder un objeto, como una mejora, que aparece al
+    /// romperse la caja.
+    Caja(i32, Option<Box<Objeto>>),
+
     /// Representa una pared en el laberinto.
     Pared,
 
     /// Representa un desvío con una dirección específica.
     Desvio(super::direccion::Direccion),
 
+    /// Representa un jugador identificado por un número. Si el jugador dejó una bomba en su
+    /// celda y todavía no se movió, la bomba queda debajo de él.
+    Jugador(u32, Option<Box<Objeto>>),
+
+    /// Representa un teletransportador identificado por un número del 1 al 9. Cada
+    /// teletransportador tiene una pareja con el mismo número: una explosión que entra en uno
+    /// sigue desde el otro en la misma dirección.
+    Teletransportador(u8),
+
+    /// Representa una mejora que el jugador recoge al entrar en la celda.
+    Mejora(super::mejora::Mejora),
+
     /// Representa una celda vacía en el laberinto.
     Vacio,
 }
+
+impl Objeto {
+    /// Crea una bomba con el alcance, el patrón y la mecha indicados. Las bombas en cruz no
+    /// guardan el patrón, así que se escriben y se vuelven a leer como el mismo objeto.
+    pub fn bomba(alcance: i32, patron: Patron, traspaso: bool, mecha: Option<i32>) -> Objeto {
+        match (patron, traspaso, mecha) {
+            (Patron::Cruz, false, None) => Objeto::Bomba(alcance),
+            (Patron::Cruz, false, Some(mecha)) => Objeto::BombaTemporizada(alcance, mecha),
+            (Patron::Cruz, true, None) => Objeto::BombaTraspaso(alcance),
+            (Patron::Cruz, true, Some(mecha)) => Objeto::BombaTraspasoTemporizada(alcance, mecha),
+            _ => Objeto::BombaConPatron {
+                alcance,
+                patron,
+                traspaso,
+                mecha,
+            },
+        }
+    }
+
+    /// Si el objeto es una bomba de cualquier tipo, devuelve su alcance e indica si es de
+    /// traspaso.
+    pub fn como_bomba(&self) -> Option<(i32, bool)> {
+        match self {
+            Objeto::Bomba(alcance) | Objeto::BombaTemporizada(alcance, _) => {
+                Some((*alcance, false))
+            }
+            Objeto::BombaTraspaso(alcance) | Objeto::BombaTraspasoTemporizada(alcance, _) => {
+                Some((*alcance, true))
+            }
+            Objeto::BombaConPatron {
+                alcance, traspaso, ..
+            } => Some((*alcance, *traspaso)),
+            Objeto::Jugador(_, Some(bomba)) => bomba.como_bomba(),
+            _ => None,
+        }
+    }
+
+    /// Si el objeto es una bomba de cualquier tipo, devuelve el patrón de su explosión. Las
+    /// bombas sin patrón explícito explotan en cruz.
+    pub fn patron(&self) -> Option<Patron> {
+        match self {
+            Objeto::BombaConPatron { patron, .. } => Some(*patron),
+            Objeto::Jugador(_, Some(bomba)) => bomba.patron(),
+            _ => self.como_bomba().map(|_| Patron::Cruz),
+        }
+    }
+
+    /// Si el objeto es una bomba temporizada, o un jugador parado sobre una, devuelve su mecha.
+    pub fn mecha(&self) -> Option<i32> {
+        match self {
+            Objeto::BombaTemporizada(_, mecha) | Objeto::BombaTraspasoTemporizada(_, mecha) => {
+                Some(*mecha)
+            }
+            Objeto::BombaConPatron { mecha, .. } => *mecha,
+            Objeto::Jugador(_, Some(bomba)) => bomba.mecha(),
+            _ => None,
+        }
+    }
+
+    /// Igual que [`Objeto::mecha`], pero permite modificar la mecha.
+    pub fn mecha_mut(&mut self) -> Option<&mut i32> {
+        match self {
+            Objeto::BombaTemporizada(_, mecha) | Objeto::BombaTraspasoTemporizada(_, mecha) => {
+                Some(mecha)
+            }
+            Objeto::BombaConPatron { mecha, .. } => mecha.as_mut(),
+            Objeto::Jugador(_, Some(bomba)) => bomba.mecha_mut(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Objeto {
+    /// Escribe el objeto con el mismo formato que se usa en los archivos de laberinto.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Objeto::Enemigo(vidas) => write!(f, "F{}", vidas),
+            Objeto::Bomba(alcance) => write!(f, "B{}", alcance),
+            Objeto::BombaTraspaso(alcance) => write!(f, "S{}", alcance),
+            Objeto::BombaTemporizada(alcance, mecha) => write!(f, "B{}:{}", alcance, mecha),
+            Objeto::BombaTraspasoTemporizada(alcance, mecha) => {
+                write!(f, "S{}:{}", alcance, mecha)
+            }
+            Objeto::BombaConPatron {
+                alcance,
+                patron,
+                traspaso,
+                mecha,
+            } => {
+                let letra = if *traspaso { "S" } else { "B" };
+                write!(f, "{}{}{}", letra, alcance, patron)?;
+                match mecha {
+                    Some(mecha) => write!(f, ":{}", mecha),
+                    None => Ok(()),
+                }
+            }
+            Objeto::Roca => write!(f, "R"),
+            Objeto::Caja(resistencia, None) => write!(f, "C{}", resistencia),
+            Objeto::Caja(resistencia, Some(contenido)) => {
+                write!(f, "C{}+{}", resistencia, contenido)
+            }
+            Objeto::Pared => write!(f, "W"),
+            Objeto::Desvio(direccion) => write!(f, "D{}", direccion),
+            Objeto::Jugador(id, None) => write!(f, "P{}", id),
+            Objeto::Jugador(id, Some(bomba)) => write!(f, "P{}+{}", id, bomba),
+            Objeto::Teletransportador(id) => write!(f, "T{}", id),
+            Objeto::Mejora(mejora) => write!(f, "M{}", mejora),
+            Objeto::Vacio => write!(f, "_"),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+
+use super::direccion::Direccion;
+
+/// Forma en la que se propaga la explosión de una bomba.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Patron {
+    /// En las cuatro direcciones cardinales. Es el patrón de las bombas comunes.
+    Cruz,
+
+    /// En las cuatro diagonales.
+    Equis,
+
+    /// En las ocho direcciones.
+    Estrella,
+
+    /// Todas las celdas del cuadrado centrado en la bomba cuyo radio es el alcance. No se
+    /// propaga en línea recta, así que no la detienen paredes, rocas ni cajas, ni la afectan
+    /// desvíos ni teletransportadores; las paredes y las rocas del cuadrado no sufren daño.
+    Cuadrado,
+}
+
+impl Patron {
+    /// Direcciones en las que se propaga la explosión, en orden. Vacío para
+    /// [`Patron::Cuadrado`], que no se propaga en línea recta.
+    pub fn direcciones(&self) -> &'static [Direccion] {
+        match self {
+            Patron::Cruz => &Direccion::CARDINALES,
+            Patron::Equis => &Direccion::DIAGONALES,
+            Patron::Estrella => &Direccion::TODAS,
+            Patron::Cuadrado => &[],
+        }
+    }
+}
+
+impl fmt::Display for Patron {
+    /// Escribe el sufijo con el que se indica el patrón de una bomba en los archivos de
+    /// laberinto. La cruz no tiene sufijo.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sufijo = match self {
+            Patron::Cruz => "",
+            Patron::Equis => "X",
+            Patron::Estrella => "*",
+            Patron::Cuadrado => "#",
+        };
+        write!(f, "{}", sufijo)
+    }
+}
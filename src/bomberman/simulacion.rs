@@ -0,0 +1,473 @@
+use super::detonar_bombas_con_traza;
+use super::enemigo::{destino_libre, ComportamientoEnemigo};
+use super::jugador::{self, EstadoJugador, Movimiento};
+use super::model::direccion::Direccion;
+use super::model::error::MovimientoError;
+use super::model::evento::EventoExplosion;
+use super::model::laberinto::Laberinto;
+use super::model::objeto::Objeto;
+use super::ModoDetonacion;
+use std::collections::HashMap;
+use std::io;
+
+/// Simulación por turnos de un laberinto con bombas temporizadas y enemigos que se mueven.
+///
+/// En cada turno primero se mueven los enemigos móviles, uno por vez en el orden en que se
+/// agregaron, cada uno según su [`ComportamientoEnemigo`]. Después se descuenta uno a la
+/// mecha de todas las bombas temporizadas; las que llegan a cero explotan juntas, como en
+/// [`ModoDetonacion::Simultaneo`]. Una bomba temporizada alcanzada por una explosión explota
+/// en ese momento, sin esperar a su mecha. Las bombas sin mecha sólo explotan si las alcanza
+/// otra explosión.
+///
+/// Los movimientos de los jugadores y las bombas que colocan se aplican en el momento, antes
+/// del siguiente turno. La simulación lleva el [`EstadoJugador`] de cada jugador, que cambia
+/// con las mejoras que recoge.
+///
+/// # Ejemplo
+///
+/// ```
+/// use bomberman_r::bomberman::simulacion::Simulacion;
+///
+/// let mut simulacion = Simulacion::new("B1:2 F1".parse().unwrap());
+/// simulacion.tick().unwrap();
+/// assert_eq!(simulacion.laberinto().to_string(), "B1:1 F1");
+/// simulacion.tick().unwrap();
+/// assert_eq!(simulacion.laberinto().to_string(), "_ _");
+/// assert_eq!(simulacion.instantaneas().len(), 3);
+/// ```
+#[derive(Debug)]
+pub struct Simulacion {
+    laberinto: Laberinto,
+    turno: usize,
+    instantaneas: Vec<Laberinto>,
+    enemigos: Vec<EnemigoMovil>,
+    jugadores: HashMap<u32, EstadoJugador>,
+    // Bombas colocadas por cada jugador con `colocar_bomba`, para respetar su capacidad
+    bombas_colocadas: Vec<(u32, usize, usize)>,
+}
+
+// Enemigo que se mueve en cada turno, con la posición en la que está
+#[derive(Debug)]
+struct EnemigoMovil {
+    x: usize,
+    y: usize,
+    comportamiento: Box<dyn ComportamientoEnemigo>,
+}
+
+impl Simulacion {
+    /// Crea una simulación a partir del estado inicial del laberinto.
+    pub fn new(laberinto: Laberinto) -> Self {
+        Simulacion {
+            instantaneas: vec![laberinto.clone()],
+            laberinto,
+            turno: 0,
+            enemigos: Vec::new(),
+            jugadores: HashMap::new(),
+            bombas_colocadas: Vec::new(),
+        }
+    }
+
+    /// Hace que el enemigo en `(x, y)` se mueva en cada turno según `comportamiento`. Si el
+    /// enemigo ya se movía, se reemplaza su comportamiento.
+    ///
+    /// # Ejemplo
+    ///
+    /// ```
+    /// use bomberman_r::bomberman::enemigo::Patrulla;
+    /// use bomberman_r::bomberman::model::direccion::Direccion;
+    /// use bomberman_r::bomberman::simulacion::Simulacion;
+    ///
+    /// let mut simulacion = Simulacion::new("F1 _ _".parse().unwrap());
+    /// simulacion
+    ///     .agregar_enemigo_movil(0, 0, Box::new(Patrulla::new(Direccion::Derecha)))
+    ///     .unwrap();
+    /// simulacion.tick().unwrap();
+    /// assert_eq!(simulacion.laberinto().to_string(), "_ F1 _");
+    /// ```
+    pub fn agregar_enemigo_movil(
+        &mut self,
+        x: usize,
+        y: usize,
+        comportamiento: Box<dyn ComportamientoEnemigo>,
+    ) -> Result<(), MovimientoError> {
+        let es_enemigo = self
+            .laberinto
+            .grid
+            .get(y)
+            .and_then(|fila| fila.get(x))
+            .is_some_and(|celda| matches!(celda.objeto, Objeto::Enemigo(_)));
+        if !es_enemigo {
+            return Err(MovimientoError::EnemigoInexistente { x, y });
+        }
+
+        match self
+            .enemigos
+            .iter_mut()
+            .find(|enemigo| (enemigo.x, enemigo.y) == (x, y))
+        {
+            Some(enemigo) => enemigo.comportamiento = comportamiento,
+            None => self.enemigos.push(EnemigoMovil {
+                x,
+                y,
+                comportamiento,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Avanza un turno y devuelve los eventos de las explosiones que ocurrieron en él.
+    pub fn tick(&mut self) -> Result<Vec<EventoExplosion>, io::Error> {
+        self.turno += 1;
+        self.mover_enemigos();
+
+        let mut vencidas = Vec::new();
+        for fila in self.laberinto.grid.iter_mut() {
+            for celda in fila.iter_mut() {
+                if let Some(mecha) = celda.objeto.mecha_mut() {
+                    *mecha -= 1;
+                    if *mecha <= 0 {
+                        vencidas.push((celda.x, celda.y));
+                    }
+                }
+            }
+        }
+
+        let eventos = if vencidas.is_empty() {
+            Vec::new()
+        } else {
+            detonar_bombas_con_traza(&mut self.laberinto, &vencidas, ModoDetonacion::Simultaneo)?
+        };
+
+        // Los enemigos eliminados dejan de moverse
+        let grid = &self.laberinto.grid;
+        self.enemigos
+            .retain(|enemigo| matches!(grid[enemigo.y][enemigo.x].objeto, Objeto::Enemigo(_)));
+
+        self.instantaneas.push(self.laberinto.clone());
+        Ok(eventos)
+    }
+
+    // Mueve a cada enemigo móvil en la dirección que elige su comportamiento, si la celda de
+    // destino está libre
+    fn mover_enemigos(&mut self) {
+        for enemigo in self.enemigos.iter_mut() {
+            let Some(direccion) =
+                enemigo
+                    .comportamiento
+                    .elegir_movimiento(&self.laberinto, enemigo.x, enemigo.y)
+            else {
+                continue;
+            };
+            if let Some((x, y)) = destino_libre(&self.laberinto, enemigo.x, enemigo.y, &direccion) {
+                let grid = &mut self.laberinto.grid;
+                grid[y][x].objeto =
+                    std::mem::replace(&mut grid[enemigo.y][enemigo.x].objeto, Objeto::Vacio);
+                enemigo.x = x;
+                enemigo.y = y;
+            }
+        }
+    }
+
+    /// Indica si ya no quedan bombas temporizadas por explotar.
+    pub fn terminada(&self) -> bool {
+        !self
+            .laberinto
+            .grid
+            .iter()
+            .flatten()
+            .any(|celda| celda.objeto.mecha().is_some())
+    }
+
+    /// Mueve al jugador `id` en el turno actual, con las reglas de [`jugador::mover_jugador`].
+    /// Si recoge una mejora, se aplica a su estado.
+    pub fn mover_jugador(
+        &mut self,
+        id: u32,
+        direccion: &Direccion,
+    ) -> Result<Movimiento, MovimientoError> {
+        let movimiento = jugador::mover_jugador(&mut self.laberinto, id, direccion)?;
+        if let Some(mejora) = movimiento.mejora {
+            self.jugadores.entry(id).or_default().aplicar(mejora);
+        }
+        Ok(movimiento)
+    }
+
+    /// Deja `bomba` en la celda del jugador `id` en el turno actual, con las reglas de
+    /// [`jugador::soltar_bomba`]. No tiene en cuenta el estado del jugador; para eso está
+    /// [`Simulacion::colocar_bomba`].
+    pub fn soltar_bomba(&mut self, id: u32, bomba: Objeto) -> Result<(), MovimientoError> {
+        jugador::soltar_bomba(&mut self.laberinto, id, bomba)
+    }
+
+    /// Coloca en la celda del jugador `id` una bomba con el alcance y el tipo de su estado y
+    /// la `mecha` indicada. Falla si el jugador ya tiene colocadas tantas bombas como su
+    /// capacidad y ninguna explotó todavía.
+    ///
+    /// # Ejemplo
+    ///
+    /// ```
+    /// use bomberman_r::bomberman::model::direccion::Direccion;
+    /// use bomberman_r::bomberman::simulacion::Simulacion;
+    ///
+    /// let mut simulacion = Simulacion::new("P1 MA _".parse().unwrap());
+    /// simulacion.mover_jugador(1, &Direccion::Derecha).unwrap();
+    /// simulacion.colocar_bomba(1, 3).unwrap();
+    /// assert_eq!(simulacion.laberinto().to_string(), "_ P1+B2:3 _");
+    /// ```
+    pub fn colocar_bomba(&mut self, id: u32, mecha: i32) -> Result<(), MovimientoError> {
+        // Las bombas que ya explotaron dejan lugar para otras
+        let grid = &self.laberinto.grid;
+        self.bombas_colocadas
+            .retain(|&(_, x, y)| grid[y][x].objeto.como_bomba().is_some());
+
+        let estado = self.estado_jugador(id);
+        let colocadas = self
+            .bombas_colocadas
+            .iter()
+            .filter(|(otro, _, _)| *otro == id)
+            .count();
+        if colocadas >= estado.capacidad {
+            return Err(MovimientoError::SinBombasDisponibles(id));
+        }
+
+        jugador::soltar_bomba(&mut self.laberinto, id, estado.bomba(mecha))?;
+        if let Some((x, y)) = jugador::posicion_jugador(&self.laberinto, id) {
+            self.bombas_colocadas.push((id, x, y));
+        }
+        Ok(())
+    }
+
+    /// Estado del jugador `id`. Un jugador que todavía no recogió mejoras tiene el estado
+    /// inicial.
+    pub fn estado_jugador(&self, id: u32) -> EstadoJugador {
+        self.jugadores.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Estado actual del laberinto.
+    pub fn laberinto(&self) -> &Laberinto {
+        &self.laberinto
+    }
+
+    /// Cantidad de turnos simulados hasta el momento.
+    pub fn turno(&self) -> usize {
+        self.turno
+    }
+
+    /// Estado del laberinto al comienzo de la simulación y al final de cada turno. La
+    /// instantánea `i` corresponde al final del turno `i`.
+    pub fn instantaneas(&self) -> &[Laberinto] {
+        &self.instantaneas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bomberman::enemigo::{CaminataAleatoria, Patrulla, PersecucionJugador};
+
+    fn simulacion(laberinto: &str) -> Simulacion {
+        Simulacion::new(laberinto.parse().unwrap())
+    }
+
+    #[test]
+    fn test_la_mecha_se_consume_por_turno() {
+        let mut simulacion = simulacion("B1:3 F2\n_ S1:1");
+
+        assert!(!simulacion.tick().unwrap().is_empty());
+        assert_eq!(simulacion.laberinto().to_string(), "B1:2 F1\n_ _");
+
+        assert!(simulacion.tick().unwrap().is_empty());
+        assert_eq!(simulacion.laberinto().to_string(), "B1:1 F1\n_ _");
+
+        simulacion.tick().unwrap();
+        assert_eq!(simulacion.laberinto().to_string(), "_ _\n_ _");
+        assert_eq!(simulacion.turno(), 3);
+        assert!(simulacion.terminada());
+    }
+
+    #[test]
+    fn test_bomba_alcanzada_explota_antes_de_tiempo() {
+        let mut simulacion = simulacion("B1:1 B1:9 F1 F1");
+
+        let eventos = simulacion.tick().unwrap();
+
+        assert!(eventos.contains(&EventoExplosion::BombaActivada {
+            x: 1,
+            y: 0,
+            profundidad: 1
+        }));
+        assert_eq!(simulacion.laberinto().to_string(), "_ _ _ F1");
+    }
+
+    #[test]
+    fn test_bombas_vencidas_explotan_a_la_vez() {
+        // Cada enemigo pierde una vida por cada bomba que lo alcanza
+        let mut simulacion = simulacion("B1:1 F2 B1:1");
+
+        simulacion.tick().unwrap();
+
+        assert_eq!(simulacion.laberinto().to_string(), "_ _ _");
+    }
+
+    #[test]
+    fn test_bombas_sin_mecha_no_explotan_solas() {
+        let mut simulacion = simulacion("B1 F1");
+
+        assert!(simulacion.terminada());
+        assert!(simulacion.tick().unwrap().is_empty());
+        assert_eq!(simulacion.laberinto().to_string(), "B1 F1");
+    }
+
+    #[test]
+    fn test_jugador_escapa_de_su_bomba() {
+        let mut simulacion = simulacion("P1 _ _\n_ F1 _");
+
+        simulacion
+            .soltar_bomba(1, Objeto::BombaTemporizada(1, 2))
+            .unwrap();
+        simulacion.mover_jugador(1, &Direccion::Derecha).unwrap();
+        simulacion.tick().unwrap();
+        simulacion.mover_jugador(1, &Direccion::Derecha).unwrap();
+        let eventos = simulacion.tick().unwrap();
+
+        assert!(eventos
+            .iter()
+            .all(|evento| !matches!(evento, EventoExplosion::JugadorEliminado { .. })));
+        assert_eq!(simulacion.laberinto().to_string(), "_ _ P1\n_ F1 _");
+    }
+
+    #[test]
+    fn test_jugador_muere_sobre_su_bomba() {
+        let mut simulacion = simulacion("P1 _");
+
+        simulacion
+            .soltar_bomba(1, Objeto::BombaTemporizada(1, 1))
+            .unwrap();
+        let eventos = simulacion.tick().unwrap();
+
+        assert!(eventos.contains(&EventoExplosion::JugadorEliminado {
+            x: 0,
+            y: 0,
+            jugador: 1
+        }));
+        assert_eq!(simulacion.laberinto().to_string(), "_ _");
+    }
+
+    #[test]
+    fn test_enemigos_moviles() {
+        let mut simulacion = simulacion("F2 _ _ W\n_ W _ _\nF1 _ _ P1");
+        simulacion
+            .agregar_enemigo_movil(0, 0, Box::new(Patrulla::new(Direccion::Derecha)))
+            .unwrap();
+        simulacion
+            .agregar_enemigo_movil(0, 2, Box::new(PersecucionJugador))
+            .unwrap();
+        assert_eq!(
+            simulacion.agregar_enemigo_movil(1, 0, Box::new(PersecucionJugador)),
+            Err(MovimientoError::EnemigoInexistente { x: 1, y: 0 })
+        );
+
+        simulacion.tick().unwrap();
+        assert_eq!(
+            simulacion.laberinto().to_string(),
+            "_ F2 _ W\n_ W _ _\n_ F1 _ P1"
+        );
+        simulacion.tick().unwrap();
+        simulacion.tick().unwrap();
+        assert_eq!(
+            simulacion.laberinto().to_string(),
+            "_ F2 _ W\n_ W _ _\n_ _ F1 P1"
+        );
+    }
+
+    #[test]
+    fn test_enemigos_eliminados_dejan_de_moverse() {
+        let mut simulacion = simulacion("F1 _ _ B1:2");
+        simulacion
+            .agregar_enemigo_movil(0, 0, Box::new(Patrulla::new(Direccion::Derecha)))
+            .unwrap();
+
+        simulacion.tick().unwrap();
+        simulacion.tick().unwrap();
+        assert_eq!(simulacion.laberinto().to_string(), "_ _ _ _");
+        assert!(simulacion.enemigos.is_empty());
+    }
+
+    #[test]
+    fn test_caminata_aleatoria_se_repite_con_la_misma_semilla() {
+        let recorrido = |semilla| {
+            let mut simulacion = simulacion("_ _ _ _\n_ F1 W _\n_ _ R _\nF2 _ _ _");
+            simulacion
+                .agregar_enemigo_movil(1, 1, Box::new(CaminataAleatoria::new(semilla)))
+                .unwrap();
+            simulacion
+                .agregar_enemigo_movil(0, 3, Box::new(CaminataAleatoria::new(semilla + 1)))
+                .unwrap();
+            for _ in 0..30 {
+                simulacion.tick().unwrap();
+            }
+            simulacion.instantaneas().to_vec()
+        };
+
+        let instantaneas = recorrido(11);
+        assert_eq!(instantaneas, recorrido(11));
+        assert_ne!(instantaneas, recorrido(12));
+        for laberinto in &instantaneas {
+            assert_eq!(laberinto.grid[1][2].objeto, Objeto::Pared);
+            assert_eq!(laberinto.grid[2][2].objeto, Objeto::Roca);
+        }
+    }
+
+    #[test]
+    fn test_mejoras_y_capacidad_de_bombas() {
+        let mut simulacion = simulacion("P1 _ MB MT _");
+
+        simulacion.colocar_bomba(1, 5).unwrap();
+        simulacion.mover_jugador(1, &Direccion::Derecha).unwrap();
+        assert_eq!(
+            simulacion.colocar_bomba(1, 5),
+            Err(MovimientoError::SinBombasDisponibles(1))
+        );
+
+        // Con las mejoras recogidas puede colocar una bomba más, y es de traspaso
+        simulacion.mover_jugador(1, &Direccion::Derecha).unwrap();
+        simulacion.mover_jugador(1, &Direccion::Derecha).unwrap();
+        assert_eq!(
+            simulacion.estado_jugador(1),
+            EstadoJugador {
+                alcance: 1,
+                traspaso: true,
+                capacidad: 2
+            }
+        );
+        simulacion.colocar_bomba(1, 1).unwrap();
+        simulacion.mover_jugador(1, &Direccion::Derecha).unwrap();
+        assert_eq!(simulacion.laberinto().to_string(), "B1:5 _ _ S1:1 P1");
+
+        let eventos = simulacion.tick().unwrap();
+        assert!(eventos.contains(&EventoExplosion::JugadorEliminado {
+            x: 4,
+            y: 0,
+            jugador: 1
+        }));
+        assert_eq!(simulacion.laberinto().to_string(), "B1:4 _ _ _ _");
+    }
+
+    #[test]
+    fn test_instantaneas() {
+        let mut simulacion = simulacion("F1 B1:2 B1");
+        simulacion.tick().unwrap();
+        simulacion.tick().unwrap();
+        simulacion.tick().unwrap();
+
+        let instantaneas: Vec<String> = simulacion
+            .instantaneas()
+            .iter()
+            .map(|laberinto| laberinto.to_string())
+            .collect();
+        assert_eq!(
+            instantaneas,
+            vec!["F1 B1:2 B1", "F1 B1:1 B1", "_ _ _", "_ _ _"]
+        );
+    }
+}
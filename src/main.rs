@@ -1,45 +1,802 @@
-use crate::bomberman::{detonar_bomba, escribir_error_en_archivo, guardar_laberinto_en_archivo};
-use bomberman::model::laberinto::Laberinto;
+use bomberman_r::bomberman::analisis::{evaluar_bombas, secuencia_minima};
+use bomberman_r::bomberman::generador::{
+    generar, generar_puzzle, DisposicionParedes, ParametrosGeneracion, ParametrosPuzzle,
+};
+use bomberman_r::bomberman::lote::{en_paralelo, expandir_patron, leer_manifiesto};
+use bomberman_r::bomberman::model::error::{LaberintoError, ManifiestoError};
+use bomberman_r::bomberman::model::laberinto::Laberinto;
+use bomberman_r::bomberman::{
+    detonar_bombas, escribir_error_en_archivo, guardar_laberinto_en_archivo, ModoDetonacion,
+};
+use std::collections::{HashMap, HashSet};
 use std::env;
-mod bomberman;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-fn main() {
-    if let Err(error) = run_program() {
-        eprintln!("Error: {}", error);
+// Argumento que indica la entrada o la salida estándar en lugar de un archivo
+const ESTANDAR: &str = "-";
+
+const AYUDA: &str = "\
+Uso: bomberman_r <subcomando> [opciones]
+
+Subcomandos:
+  detonar   Detona bombas y guarda el laberinto resultante
+  validar   Verifica que los laberintos se puedan cargar
+  mostrar   Muestra un laberinto, opcionalmente después de detonar bombas
+  resolver  Ordena las bombas o busca la secuencia mínima que elimina a los enemigos
+  generar   Genera un laberinto o un puzzle al azar
+  diff      Muestra las celdas en las que difieren dos laberintos
+  lote      Detona las bombas de un manifiesto en muchos laberintos a la vez
+
+También se acepta la forma anterior:
+  bomberman_r <entrada> <dir_salida> x y [x y ...] [--simultaneo]
+
+Un `-` en lugar de un laberinto de entrada lo lee de la entrada estándar, y en lugar de
+<dir_salida> o del archivo de `generar` escribe el resultado en la salida estándar. Si la
+salida estándar recibe un error, es una línea `ERROR: ...`.
+
+Use `bomberman_r <subcomando> --help` para ver las opciones de cada subcomando.
+
+Códigos de salida:
+  0  Éxito
+  1  `diff` encontró diferencias
+  2  Argumentos inválidos
+  3  No se pudo cargar un laberinto
+  4  No se pudo detonar una bomba
+  5  No se pudo guardar el resultado
+  6  No se pudo generar el laberinto
+  7  Algún archivo de `lote` terminó con error";
+
+const USO_DETONAR: &str = "\
+Uso: detonar <entrada> <dir_salida> --bomba X,Y [--bomba X,Y ...] [--simultaneo]
+
+Detona las bombas indicadas, en orden o todas a la vez con --simultaneo, y guarda el laberinto
+resultante en <dir_salida> con el mismo nombre que <entrada>. Si algo falla, el archivo de
+salida contiene una línea `ERROR: ...`.
+
+Con `-` como <entrada> el laberinto se lee de la entrada estándar y el archivo de salida se
+llama `stdin`. Con `-` como <dir_salida> el laberinto resultante, o la línea de error, se
+escribe en la salida estándar.";
+
+const USO_VALIDAR: &str = "\
+Uso: validar <entrada> [<entrada> ...]
+
+Carga cada laberinto e informa si es válido y si tiene ciclos de desvíos.";
+
+const USO_MOSTRAR: &str = "\
+Uso: mostrar <entrada> [--bomba X,Y ...] [--simultaneo]
+
+Muestra el laberinto por salida estándar, después de detonar las bombas indicadas.";
+
+const USO_RESOLVER: &str = "\
+Uso: resolver <entrada> [--secuencia]
+
+Muestra las bombas ordenadas de mejor a peor o, con --secuencia, la menor cantidad de
+detonaciones que elimina a todos los enemigos.";
+
+const USO_GENERAR: &str = "\
+Uso: generar <archivo_salida> [opciones]
+
+Con `-` como <archivo_salida> el laberinto se escribe en la salida estándar.
+
+Opciones:
+  --semilla N              Semilla; sin ella se usa una basada en la hora
+  --ancho N, --alto N      Tamaño del laberinto (7x7 por defecto)
+  --paredes TIPO           pilares (por defecto) o aleatorias
+  --densidad-paredes D     Densidad de las paredes aleatorias (0.2 por defecto); sólo con
+                           --paredes aleatorias
+  --rocas D, --enemigos D, --bombas D, --traspaso D, --desvios D
+                           Densidad de cada objeto, entre 0 y 1
+  --vidas N, --alcance N   Vidas máximas de los enemigos y alcance máximo de las bombas
+  --puzzle                 Genera un puzzle y escribe su solución con extensión .solucion
+  --detonaciones N         Detonaciones de la solución del puzzle (1 por defecto)
+  --cantidad-enemigos N    Enemigos del puzzle (3 por defecto)
+  --varias-soluciones      No exige que la solución del puzzle sea única";
+
+const USO_DIFF: &str = "\
+Uso: diff <laberinto_a> <laberinto_b>
+
+Muestra las celdas en las que difieren dos laberintos. Termina con código 1 si hay
+diferencias.";
+
+const USO_LOTE: &str = "\
+Uso: lote <directorio|patrón> [...] <dir_salida> --manifiesto <archivo> [--simultaneo]
+            [--hilos N]
+
+Detona en cada laberinto las bombas que indica el manifiesto y guarda el resultado en
+<dir_salida> con el mismo nombre que la entrada. Los laberintos se procesan en paralelo, con
+tantos hilos como núcleos si no se indica --hilos. Un patrón como `ejemplos/*.txt` elige los
+archivos cuyo nombre coincide, con `*` y `?` como comodines.
+
+Cada línea del manifiesto tiene el nombre de un archivo seguido de las coordenadas de sus
+bombas, por ejemplo `ejemplo_1.txt 0 0 2 2`; las que empiezan con `#` se ignoran. Como el
+manifiesto y la salida usan sólo el nombre, no puede haber dos laberintos con el mismo nombre
+en distintos directorios. Al final se muestra una tabla con el resultado de cada archivo.";
+
+// Error de la línea de comandos. Cada clase de error termina el programa con un código
+// distinto.
+#[derive(Debug)]
+enum ErrorCli {
+    Uso(String),
+    Carga(String),
+    Detonacion(String),
+    Escritura(String),
+    Generacion(String),
+    Lote(String),
+}
+
+impl ErrorCli {
+    fn uso(mensaje: impl fmt::Display, uso: &str) -> Self {
+        ErrorCli::Uso(format!("{}\n\n{}", mensaje, uso))
+    }
+
+    fn codigo(&self) -> u8 {
+        match self {
+            ErrorCli::Uso(_) => 2,
+            ErrorCli::Carga(_) => 3,
+            ErrorCli::Detonacion(_) => 4,
+            ErrorCli::Escritura(_) => 5,
+            ErrorCli::Generacion(_) => 6,
+            ErrorCli::Lote(_) => 7,
+        }
     }
 }
 
-fn run_program() -> Result<(), String> {
-    let args: Vec<String> = env::args().collect();
+impl fmt::Display for ErrorCli {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCli::Uso(mensaje)
+            | ErrorCli::Carga(mensaje)
+            | ErrorCli::Detonacion(mensaje)
+            | ErrorCli::Escritura(mensaje)
+            | ErrorCli::Generacion(mensaje)
+            | ErrorCli::Lote(mensaje) => write!(f, "{}", mensaje),
+        }
+    }
+}
 
-    if args.len() != 5 {
-        return Err("Argumentos inválidos".to_string());
+// Argumentos de un subcomando: los posicionales y las opciones, en el orden en que aparecen
+struct Argumentos {
+    posicionales: Vec<String>,
+    opciones: Vec<(String, Option<String>)>,
+}
+
+impl Argumentos {
+    // `con_valor` y `sin_valor` son las opciones que acepta el subcomando
+    fn separar(
+        args: &[String],
+        con_valor: &[&str],
+        sin_valor: &[&str],
+        uso: &str,
+    ) -> Result<Self, ErrorCli> {
+        let mut argumentos = Argumentos {
+            posicionales: Vec::new(),
+            opciones: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if con_valor.contains(&arg.as_str()) {
+                let valor = args
+                    .next()
+                    .ok_or_else(|| ErrorCli::uso(format!("Falta el valor de {}", arg), uso))?;
+                argumentos.opciones.push((arg.clone(), Some(valor.clone())));
+            } else if sin_valor.contains(&arg.as_str()) {
+                argumentos.opciones.push((arg.clone(), None));
+            } else if arg.starts_with("--") {
+                return Err(ErrorCli::uso(format!("Opción desconocida: {}", arg), uso));
+            } else {
+                argumentos.posicionales.push(arg.clone());
+            }
+        }
+        Ok(argumentos)
+    }
+
+    fn tiene(&self, opcion: &str) -> bool {
+        self.opciones.iter().any(|(nombre, _)| nombre == opcion)
     }
 
-    let mut laberinto = match Laberinto::cargar(&args[1]) {
+    fn valores<'a>(&'a self, opcion: &'a str) -> impl Iterator<Item = &'a str> {
+        self.opciones
+            .iter()
+            .filter(move |(nombre, _)| nombre == opcion)
+            .filter_map(|(_, valor)| valor.as_deref())
+    }
+}
+
+fn main() -> ExitCode {
+    match run_program() {
+        Ok(codigo) => codigo,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            ExitCode::from(error.codigo())
+        }
+    }
+}
+
+fn run_program() -> Result<ExitCode, ErrorCli> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    type Subcomando = fn(&[String]) -> Result<ExitCode, ErrorCli>;
+    let (uso, subcomando): (&str, Subcomando) = match args.first().map(String::as_str) {
+        None => return Err(ErrorCli::Uso(AYUDA.to_string())),
+        Some("-h" | "--help") => {
+            println!("{}", AYUDA);
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some("detonar") => (USO_DETONAR, detonar),
+        Some("validar") => (USO_VALIDAR, validar),
+        Some("mostrar") => (USO_MOSTRAR, mostrar),
+        Some("resolver") => (USO_RESOLVER, resolver),
+        Some("generar") => (USO_GENERAR, generar_laberinto),
+        Some("diff") => (USO_DIFF, diferencias),
+        Some("lote") => (USO_LOTE, lote),
+        Some(_) => return forma_posicional(&args),
+    };
+
+    let resto = &args[1..];
+    if resto.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", uso);
+        return Ok(ExitCode::SUCCESS);
+    }
+    subcomando(resto)
+}
+
+// Forma anterior a los subcomandos: <entrada> <dir_salida> x y [x y ...] [--simultaneo]
+fn forma_posicional(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let mut coordenadas_args = args.get(2..).unwrap_or_default();
+    let mut modo = ModoDetonacion::Secuencial;
+    if coordenadas_args.last().map(String::as_str) == Some("--simultaneo") {
+        modo = ModoDetonacion::Simultaneo;
+        coordenadas_args = &coordenadas_args[..coordenadas_args.len() - 1];
+    }
+
+    if coordenadas_args.is_empty() || coordenadas_args.len() % 2 != 0 {
+        return Err(ErrorCli::uso("Argumentos inválidos", AYUDA));
+    }
+
+    let mut coordenadas = Vec::new();
+    for par in coordenadas_args.chunks(2) {
+        let x: usize = par[0]
+            .parse()
+            .map_err(|_| ErrorCli::uso("No se pudo convertir x", AYUDA))?;
+
+        let y: usize = par[1]
+            .parse()
+            .map_err(|_| ErrorCli::uso("No se pudo convertir y", AYUDA))?;
+
+        coordenadas.push((x, y));
+    }
+
+    detonar_y_guardar(&args[0], &args[1], &coordenadas, modo)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn detonar(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let argumentos = Argumentos::separar(args, &["--bomba"], &["--simultaneo"], USO_DETONAR)?;
+    let [entrada, dir_salida] = argumentos.posicionales.as_slice() else {
+        return Err(ErrorCli::uso(
+            "Se esperaban <entrada> y <dir_salida>",
+            USO_DETONAR,
+        ));
+    };
+    let coordenadas = coordenadas_de(&argumentos, USO_DETONAR)?;
+    if coordenadas.is_empty() {
+        return Err(ErrorCli::uso("Falta al menos una --bomba", USO_DETONAR));
+    }
+
+    detonar_y_guardar(entrada, dir_salida, &coordenadas, modo_de(&argumentos))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// Carga `entrada`, detona las bombas y guarda el resultado en `dir_salida`. Si algo falla,
+// el archivo de salida contiene el error. Con `-` como entrada el laberinto se lee de la
+// entrada estándar, y con `-` como salida el resultado o el error se escribe en la salida
+// estándar.
+fn detonar_y_guardar(
+    entrada: &str,
+    dir_salida: &str,
+    coordenadas: &[(usize, usize)],
+    modo: ModoDetonacion,
+) -> Result<(), ErrorCli> {
+    // El archivo de salida se llama como la entrada, o `stdin` si se leyó de ahí
+    let nombre_entrada = if entrada == ESTANDAR {
+        "stdin"
+    } else {
+        entrada
+    };
+    let informar = |error: ErrorCli| {
+        if dir_salida == ESTANDAR {
+            let _ = writeln!(io::stdout().lock(), "ERROR: {}", error);
+        } else {
+            let _ = escribir_error_en_archivo(dir_salida, nombre_entrada, &error.to_string());
+        }
+        error
+    };
+
+    let mut laberinto = match leer_laberinto(entrada) {
         Ok(l) => l,
         Err(e) => {
-            let _ = escribir_error_en_archivo(&args[2], &args[1], &format!("Error al cargar el laberinto: {}", e));
-            return Err(format!("Error al cargar el laberinto: {}", e));
+            // El mensaje incluye la línea, la columna y el texto que provocó el error
+            let mensaje = format!("Error al cargar el laberinto: {}", e);
+            return Err(informar(ErrorCli::Carga(mensaje)));
         }
     };
-    let x: usize = args[3]
-        .parse()
-        .map_err(|_| "No se pudo convertir x".to_string())?;
-
-    let y: usize = args[4]
-        .parse()
-        .map_err(|_| "No se pudo convertir y".to_string())?;
+    for ciclo in laberinto.ciclos_de_desvio() {
+        eprintln!("Advertencia: {}", ciclo);
+    }
 
-    if let Err(e) = detonar_bomba(&mut laberinto, x, y) {
-        let _ = escribir_error_en_archivo(&args[2], &args[1],&format!("Error al detonar la bomba: {}", e));
-        return Err(format!("Error al detonar la bomba: {}", e));
+    if let Err(e) = detonar_bombas(&mut laberinto, coordenadas, modo) {
+        let mensaje = format!("Error al detonar la bomba: {}", e);
+        return Err(informar(ErrorCli::Detonacion(mensaje)));
     }
 
-    if let Err(e) = guardar_laberinto_en_archivo(&laberinto, &args[2], &args[1]) {
-        let _ = escribir_error_en_archivo(&args[2], &args[1], &format!("Error al guardar el laberinto: {}", e));
-        return Err(format!("Error al guardar el laberinto: {}", e));
+    let guardado = if dir_salida == ESTANDAR {
+        writeln!(io::stdout().lock(), "{}", laberinto)
+    } else {
+        guardar_laberinto_en_archivo(&laberinto, dir_salida, nombre_entrada)
+    };
+    if let Err(e) = guardado {
+        let mensaje = format!("Error al guardar el laberinto: {}", e);
+        return Err(informar(ErrorCli::Escritura(mensaje)));
     }
 
     Ok(())
 }
+
+// Verifica cada laberinto y falla si alguno no se puede cargar
+fn validar(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let argumentos = Argumentos::separar(args, &[], &[], USO_VALIDAR)?;
+    if argumentos.posicionales.is_empty() {
+        return Err(ErrorCli::uso("Falta al menos un laberinto", USO_VALIDAR));
+    }
+
+    let mut invalidos = 0;
+    for archivo in &argumentos.posicionales {
+        match leer_laberinto(archivo) {
+            Ok(laberinto) => {
+                println!(
+                    "{}: válido ({}x{})",
+                    archivo, laberinto.ancho, laberinto.alto
+                );
+                for ciclo in laberinto.ciclos_de_desvio() {
+                    println!("  advertencia: {}", ciclo);
+                }
+            }
+            Err(e) => {
+                println!("{}: {}", archivo, e);
+                invalidos += 1;
+            }
+        }
+    }
+
+    if invalidos > 0 {
+        return Err(ErrorCli::Carga(format!(
+            "{} de {} laberintos no son válidos",
+            invalidos,
+            argumentos.posicionales.len()
+        )));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Muestra el laberinto por salida estándar, después de detonar las bombas indicadas
+fn mostrar(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let argumentos = Argumentos::separar(args, &["--bomba"], &["--simultaneo"], USO_MOSTRAR)?;
+    let [entrada] = argumentos.posicionales.as_slice() else {
+        return Err(ErrorCli::uso("Se esperaba un único laberinto", USO_MOSTRAR));
+    };
+    let coordenadas = coordenadas_de(&argumentos, USO_MOSTRAR)?;
+
+    let mut laberinto = cargar(entrada)?;
+    detonar_bombas(&mut laberinto, &coordenadas, modo_de(&argumentos))
+        .map_err(|e| ErrorCli::Detonacion(format!("Error al detonar la bomba: {}", e)))?;
+
+    println!("{}", laberinto);
+    Ok(ExitCode::SUCCESS)
+}
+
+// Muestra por salida estándar las bombas del laberinto ordenadas de mejor a peor o, con
+// `--secuencia`, la menor cantidad de detonaciones que elimina a todos los enemigos.
+fn resolver(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let argumentos = Argumentos::separar(args, &[], &["--secuencia"], USO_RESOLVER)?;
+    let [archivo] = argumentos.posicionales.as_slice() else {
+        return Err(ErrorCli::uso(
+            "Se esperaba un único laberinto",
+            USO_RESOLVER,
+        ));
+    };
+
+    let laberinto = cargar(archivo)?;
+
+    if argumentos.tiene("--secuencia") {
+        match secuencia_minima(&laberinto) {
+            Some(bombas) => {
+                let coordenadas: Vec<String> = bombas
+                    .iter()
+                    .map(|(x, y)| format!("({}, {})", x, y))
+                    .collect();
+                println!(
+                    "Secuencia mínima ({} detonaciones): {}",
+                    bombas.len(),
+                    coordenadas.join(" ")
+                );
+            }
+            None => println!("No es posible eliminar a todos los enemigos"),
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let ranking = evaluar_bombas(&laberinto);
+    if ranking.is_empty() {
+        println!("El laberinto no tiene bombas");
+    }
+    for (posicion, evaluacion) in ranking.iter().enumerate() {
+        println!(
+            "{}. ({}, {}): {} enemigos eliminados, {} de daño, {} celdas despejadas",
+            posicion + 1,
+            evaluacion.x,
+            evaluacion.y,
+            evaluacion.resumen.enemigos_eliminados,
+            evaluacion.danio_total(),
+            evaluacion.celdas_despejadas
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+// Genera un laberinto al azar y lo escribe en `archivo_salida` con el formato de los archivos
+// de entrada. Sin `--semilla` se usa una basada en la hora, que se muestra para poder repetirlo.
+// Con `--puzzle` genera un puzzle y escribe su solución al lado, con extensión `.solucion`.
+fn generar_laberinto(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let argumentos = Argumentos::separar(
+        args,
+        &[
+            "--semilla",
+            "--ancho",
+            "--alto",
+            "--paredes",
+            "--densidad-paredes",
+            "--rocas",
+            "--enemigos",
+            "--bombas",
+            "--traspaso",
+            "--desvios",
+            "--vidas",
+            "--alcance",
+            "--detonaciones",
+            "--cantidad-enemigos",
+        ],
+        &["--puzzle", "--varias-soluciones"],
+        USO_GENERAR,
+    )?;
+    let [archivo] = argumentos.posicionales.as_slice() else {
+        return Err(ErrorCli::uso(
+            "Se esperaba un único archivo de salida",
+            USO_GENERAR,
+        ));
+    };
+
+    // El puzzle parte de los parámetros de la biblioteca, que tienen más bombas
+    let puzzle = argumentos.tiene("--puzzle");
+    let mut parametros_puzzle = ParametrosPuzzle::default();
+    let mut parametros = if puzzle {
+        parametros_puzzle.laberinto.clone()
+    } else {
+        ParametrosGeneracion::default()
+    };
+    let mut semilla = None;
+    let mut aleatorias = false;
+    let mut densidad_paredes = 0.2;
+
+    for (opcion, valor) in &argumentos.opciones {
+        let Some(valor) = valor.as_deref() else {
+            continue;
+        };
+        match opcion.as_str() {
+            "--semilla" => semilla = Some(parsear_valor(opcion, valor)?),
+            "--ancho" => parametros.ancho = parsear_valor(opcion, valor)?,
+            "--alto" => parametros.alto = parsear_valor(opcion, valor)?,
+            "--paredes" => {
+                aleatorias = match valor {
+                    "pilares" => false,
+                    "aleatorias" => true,
+                    _ => return Err(valor_invalido(opcion, valor, USO_GENERAR)),
+                }
+            }
+            "--densidad-paredes" => densidad_paredes = parsear_valor(opcion, valor)?,
+            "--rocas" => parametros.rocas = parsear_valor(opcion, valor)?,
+            "--enemigos" => parametros.enemigos = parsear_valor(opcion, valor)?,
+            "--bombas" => parametros.bombas = parsear_valor(opcion, valor)?,
+            "--traspaso" => parametros.bombas_traspaso = parsear_valor(opcion, valor)?,
+            "--desvios" => parametros.desvios = parsear_valor(opcion, valor)?,
+            "--vidas" => parametros.vidas_maximas = parsear_valor(opcion, valor)?,
+            "--alcance" => parametros.alcance_maximo = parsear_valor(opcion, valor)?,
+            "--detonaciones" => parametros_puzzle.detonaciones = parsear_valor(opcion, valor)?,
+            "--cantidad-enemigos" => parametros_puzzle.enemigos = parsear_valor(opcion, valor)?,
+            _ => {}
+        }
+    }
+    if aleatorias {
+        parametros.paredes = DisposicionParedes::Aleatoria(densidad_paredes);
+    } else if argumentos.tiene("--densidad-paredes") {
+        return Err(ErrorCli::uso(
+            "--densidad-paredes sólo se usa con --paredes aleatorias",
+            USO_GENERAR,
+        ));
+    }
+    parametros_puzzle.solucion_unica = !argumentos.tiene("--varias-soluciones");
+    if archivo == ESTANDAR && puzzle {
+        return Err(ErrorCli::uso(
+            "--puzzle necesita un archivo de salida para escribir la solución al lado",
+            USO_GENERAR,
+        ));
+    }
+
+    let semilla = match semilla {
+        Some(semilla) => semilla,
+        None => {
+            let semilla = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duracion| duracion.as_nanos() as u64)
+                .unwrap_or_default();
+            // Por la salida de errores, para no mezclarla con el laberinto si se escribe en `-`
+            eprintln!("Semilla: {}", semilla);
+            semilla
+        }
+    };
+
+    if let Some(dir) = Path::new(archivo).parent().filter(|_| archivo != ESTANDAR) {
+        fs::create_dir_all(dir)
+            .map_err(|e| ErrorCli::Escritura(format!("Error al guardar el laberinto: {}", e)))?;
+    }
+
+    let laberinto = if puzzle {
+        // La densidad de enemigos no se usa: el puzzle ubica los suyos
+        parametros_puzzle.laberinto = ParametrosGeneracion {
+            enemigos: 0.0,
+            ..parametros
+        };
+        let puzzle = generar_puzzle(&parametros_puzzle, semilla)
+            .map_err(|e| ErrorCli::Generacion(format!("Error al generar el puzzle: {}", e)))?;
+
+        let mut solucion = Vec::new();
+        puzzle
+            .escribir_solucion(&mut solucion)
+            .and_then(|_| fs::write(Path::new(archivo).with_extension("solucion"), solucion))
+            .map_err(|e| ErrorCli::Escritura(format!("Error al guardar la solución: {}", e)))?;
+        puzzle.laberinto
+    } else {
+        generar(&parametros, semilla)
+            .map_err(|e| ErrorCli::Generacion(format!("Error al generar el laberinto: {}", e)))?
+    };
+
+    let guardado = if archivo == ESTANDAR {
+        writeln!(io::stdout().lock(), "{}", laberinto)
+    } else {
+        fs::write(archivo, laberinto.to_string())
+    };
+    guardado.map_err(|e| ErrorCli::Escritura(format!("Error al guardar el laberinto: {}", e)))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// Muestra las celdas en las que difieren dos laberintos; termina con código 1 si hay alguna
+fn diferencias(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let argumentos = Argumentos::separar(args, &[], &[], USO_DIFF)?;
+    let [archivo_a, archivo_b] = argumentos.posicionales.as_slice() else {
+        return Err(ErrorCli::uso("Se esperaban dos laberintos", USO_DIFF));
+    };
+    let a = cargar(archivo_a)?;
+    let b = cargar(archivo_b)?;
+
+    if (a.ancho, a.alto) != (b.ancho, b.alto) {
+        println!(
+            "Los laberintos tienen tamaños distintos: {}x{} y {}x{}",
+            a.ancho, a.alto, b.ancho, b.alto
+        );
+        return Ok(ExitCode::from(1));
+    }
+
+    let mut cantidad = 0;
+    for (celda_a, celda_b) in a.grid.iter().flatten().zip(b.grid.iter().flatten()) {
+        if celda_a.objeto != celda_b.objeto {
+            println!(
+                "({}, {}): {} -> {}",
+                celda_a.x, celda_a.y, celda_a.objeto, celda_b.objeto
+            );
+            cantidad += 1;
+        }
+    }
+
+    if cantidad == 0 {
+        return Ok(ExitCode::SUCCESS);
+    }
+    println!("{} celdas distintas", cantidad);
+    Ok(ExitCode::from(1))
+}
+
+// Procesa en paralelo los laberintos que indican los patrones según el manifiesto y muestra
+// una tabla con el resultado de cada uno
+fn lote(args: &[String]) -> Result<ExitCode, ErrorCli> {
+    let argumentos = Argumentos::separar(
+        args,
+        &["--manifiesto", "--hilos"],
+        &["--simultaneo"],
+        USO_LOTE,
+    )?;
+    let (dir_salida, patrones) = match argumentos.posicionales.split_last() {
+        Some((dir_salida, patrones)) if !patrones.is_empty() => (dir_salida, patrones),
+        _ => {
+            return Err(ErrorCli::uso(
+                "Se esperaban los laberintos y <dir_salida>",
+                USO_LOTE,
+            ))
+        }
+    };
+    let Some(ruta_manifiesto) = argumentos.valores("--manifiesto").last() else {
+        return Err(ErrorCli::uso("Falta --manifiesto", USO_LOTE));
+    };
+    let hilos = match argumentos.valores("--hilos").last() {
+        Some(valor) => match valor.parse() {
+            Ok(hilos) if hilos > 0 => hilos,
+            _ => return Err(valor_invalido("--hilos", valor, USO_LOTE)),
+        },
+        None => thread::available_parallelism().map_or(1, |hilos| hilos.get()),
+    };
+
+    let manifiesto = File::open(ruta_manifiesto)
+        .map_err(ManifiestoError::from)
+        .and_then(|archivo| leer_manifiesto(BufReader::new(archivo)))
+        .map_err(|e| ErrorCli::Carga(format!("Error al leer el manifiesto: {}", e)))?;
+
+    let mut archivos = Vec::new();
+    for patron in patrones {
+        let encontrados = expandir_patron(patron)
+            .map_err(|e| ErrorCli::Carga(format!("Error al leer {}: {}", patron, e)))?;
+        archivos.extend(encontrados);
+    }
+    // El manifiesto puede estar en el mismo directorio que los laberintos
+    let manifiesto_canonico = fs::canonicalize(ruta_manifiesto).ok();
+    archivos.retain(|archivo| fs::canonicalize(archivo).ok() != manifiesto_canonico);
+    archivos.sort();
+    // Dos rutas distintas pueden llevar al mismo archivo, como `a.txt` y `./a.txt`
+    let mut vistos = HashSet::new();
+    archivos.retain(|archivo| vistos.insert(fs::canonicalize(archivo).unwrap_or(archivo.clone())));
+
+    // Cada archivo con su ruta, para la tabla, y su nombre, para buscarlo en el manifiesto
+    let trabajos: Vec<(String, String)> = archivos
+        .iter()
+        .map(|archivo| {
+            let nombre = archivo.file_name().unwrap_or_default();
+            (
+                archivo.to_string_lossy().into_owned(),
+                nombre.to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+    // El manifiesto y la salida identifican a cada laberinto sólo por su nombre
+    let mut por_nombre = HashMap::new();
+    for (ruta, nombre) in &trabajos {
+        if let Some(otra) = por_nombre.insert(nombre, ruta) {
+            return Err(ErrorCli::uso(
+                format!("{} y {} tienen el mismo nombre", otra, ruta),
+                USO_LOTE,
+            ));
+        }
+    }
+    let modo = modo_de(&argumentos);
+    let resultados = en_paralelo(&trabajos, hilos, |(ruta, nombre)| {
+        match manifiesto.get(nombre) {
+            Some(coordenadas) => detonar_y_guardar(ruta, dir_salida, coordenadas, modo),
+            None => Err(ErrorCli::Lote("no figura en el manifiesto".to_string())),
+        }
+    });
+
+    let filas: Vec<(String, Result<(), ErrorCli>)> = trabajos
+        .iter()
+        .map(|(ruta, _)| ruta.clone())
+        .zip(resultados)
+        .collect();
+    // El manifiesto puede cubrir más archivos que los elegidos, pero avisar por si es un error
+    for nombre in manifiesto.keys() {
+        if !trabajos.iter().any(|(_, encontrado)| encontrado == nombre) {
+            eprintln!(
+                "Advertencia: {} figura en el manifiesto pero no entre los laberintos",
+                nombre
+            );
+        }
+    }
+
+    let ancho = filas
+        .iter()
+        .map(|(archivo, _)| archivo.len())
+        .max()
+        .unwrap_or(0);
+    println!("{:<ancho$}  Resultado", "Archivo", ancho = ancho.max(7));
+    for (archivo, resultado) in &filas {
+        match resultado {
+            Ok(()) => println!("{:<ancho$}  ok", archivo, ancho = ancho.max(7)),
+            Err(e) => println!(
+                "{:<ancho$}  error {}: {}",
+                archivo,
+                e.codigo(),
+                e,
+                ancho = ancho.max(7)
+            ),
+        }
+    }
+
+    let errores = filas
+        .iter()
+        .filter(|(_, resultado)| resultado.is_err())
+        .count();
+    println!(
+        "\n{} archivos: {} correctos, {} con errores",
+        filas.len(),
+        filas.len() - errores,
+        errores
+    );
+    if errores > 0 {
+        return Err(ErrorCli::Lote(format!(
+            "{} de {} archivos terminaron con errores",
+            errores,
+            filas.len()
+        )));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Carga el laberinto de `archivo`, o de la entrada estándar si es `-`
+fn leer_laberinto(archivo: &str) -> Result<Laberinto, LaberintoError> {
+    if archivo == ESTANDAR {
+        Laberinto::from_reader(io::stdin().lock())
+    } else {
+        Laberinto::cargar(archivo)
+    }
+}
+
+fn cargar(archivo: &str) -> Result<Laberinto, ErrorCli> {
+    leer_laberinto(archivo)
+        .map_err(|e| ErrorCli::Carga(format!("Error al cargar el laberinto {}: {}", archivo, e)))
+}
+
+fn modo_de(argumentos: &Argumentos) -> ModoDetonacion {
+    if argumentos.tiene("--simultaneo") {
+        ModoDetonacion::Simultaneo
+    } else {
+        ModoDetonacion::Secuencial
+    }
+}
+
+// Coordenadas de las opciones `--bomba X,Y`, en orden
+fn coordenadas_de(argumentos: &Argumentos, uso: &str) -> Result<Vec<(usize, usize)>, ErrorCli> {
+    argumentos
+        .valores("--bomba")
+        .map(|valor| {
+            let (x, y) = valor
+                .split_once(',')
+                .ok_or_else(|| valor_invalido("--bomba", valor, uso))?;
+            match (x.trim().parse(), y.trim().parse()) {
+                (Ok(x), Ok(y)) => Ok((x, y)),
+                _ => Err(valor_invalido("--bomba", valor, uso)),
+            }
+        })
+        .collect()
+}
+
+fn parsear_valor<T: FromStr>(opcion: &str, valor: &str) -> Result<T, ErrorCli> {
+    valor
+        .parse()
+        .map_err(|_| valor_invalido(opcion, valor, USO_GENERAR))
+}
+
+fn valor_invalido(opcion: &str, valor: &str, uso: &str) -> ErrorCli {
+    ErrorCli::uso(format!("Valor inválido para {}: {}", opcion, valor), uso)
+}
@@ -1,5 +1,7 @@
+use bomberman_r::bomberman::generador::{generar_puzzle, ParametrosPuzzle};
 use bomberman_r::bomberman::{
-    detonar_bomba, model::celda::Celda, model::laberinto::Laberinto, model::objeto::Objeto,
+    detonar_bomba, detonar_bomba_con_traza, model::celda::Celda, model::error::LaberintoError,
+    model::evento::EventoExplosion, model::laberinto::Laberinto, model::objeto::Objeto,
 };
 
 #[test]
@@ -16,29 +18,28 @@ fn test_cargar_laberinto() {
     let ruta = "laberinto.txt";
 
     match std::fs::write(ruta, contenido) {
-        Ok(_) => {
-            match Laberinto::cargar(ruta) {
-                Ok(laberinto) => {
-                    assert_eq!(laberinto.tamano, 7);
-                    let primer_elemento = &laberinto.grid[0][0].objeto;
-                    assert_eq!(primer_elemento, &Objeto::Bomba(2));
-                    let celda_prueba = Celda {
-                        objeto: Objeto::Pared,
-                        x: 1,
-                        y: 1,
-                    };
-                    let celda_test = &laberinto.grid[1][1];
-                    assert_eq!(celda_test, &celda_prueba);
-                }
-                Err(e) => {
-                    eprintln!("Error al cargar el laberinto: {:?}", e);
-                    assert!(false);
-                }
+        Ok(_) => match Laberinto::cargar(ruta) {
+            Ok(laberinto) => {
+                assert_eq!(laberinto.ancho, 7);
+                assert_eq!(laberinto.alto, 7);
+                let primer_elemento = &laberinto.grid[0][0].objeto;
+                assert_eq!(primer_elemento, &Objeto::Bomba(2));
+                let celda_prueba = Celda {
+                    objeto: Objeto::Pared,
+                    x: 1,
+                    y: 1,
+                };
+                let celda_test = &laberinto.grid[1][1];
+                assert_eq!(celda_test, &celda_prueba);
             }
-        }
+            Err(e) => {
+                eprintln!("Error al cargar el laberinto: {:?}", e);
+                panic!();
+            }
+        },
         Err(e) => {
             eprintln!("Error al escribir en el archivo de prueba: {:?}", e);
-            assert!(false);
+            panic!();
         }
     }
 
@@ -46,7 +47,7 @@ fn test_cargar_laberinto() {
         Ok(_) => {}
         Err(e) => {
             eprintln!("Error al eliminar el archivo de prueba: {:?}", e);
-            assert!(false);
+            panic!();
         }
     }
 }
@@ -62,41 +63,23 @@ fn test_detonar_bomba_1() {
         _ W _ W _ W _\n\
         _ _ _ _ _ _ _\n";
 
-    let ruta = "laberinto.txt";
-
-    match std::fs::write(ruta, contenido) {
-        Ok(_) => {
-            match Laberinto::cargar(ruta) {
-                Ok(mut laberinto) => {
-                    let _ = detonar_bomba(&mut laberinto, 4, 2);
-                    let bomba = &laberinto.grid[2][4].objeto;
-                    assert_eq!(bomba, &Objeto::Vacio);
-                    let enemigo = &laberinto.grid[0][4].objeto;
-                    assert_eq!(enemigo, &Objeto::Vacio);
-                    let pared = &laberinto.grid[1][1].objeto;
-                    assert_eq!(pared, &Objeto::Pared);
-                    let celda1 = &laberinto.grid[0][0].objeto;
-                    assert_ne!(celda1, &Objeto::Vacio);
-                    let celda2 = &laberinto.grid[1][2].objeto;
-                    assert_ne!(celda2, &Objeto::Vacio);
-                }
-                Err(e) => {
-                    eprintln!("Error al cargar el laberinto: {:?}", e);
-                    assert!(false);
-                }
-            }
+    match contenido.parse::<Laberinto>() {
+        Ok(mut laberinto) => {
+            let _ = detonar_bomba(&mut laberinto, 4, 2);
+            let bomba = &laberinto.grid[2][4].objeto;
+            assert_eq!(bomba, &Objeto::Vacio);
+            let enemigo = &laberinto.grid[0][4].objeto;
+            assert_eq!(enemigo, &Objeto::Vacio);
+            let pared = &laberinto.grid[1][1].objeto;
+            assert_eq!(pared, &Objeto::Pared);
+            let celda1 = &laberinto.grid[0][0].objeto;
+            assert_ne!(celda1, &Objeto::Vacio);
+            let celda2 = &laberinto.grid[1][2].objeto;
+            assert_ne!(celda2, &Objeto::Vacio);
         }
         Err(e) => {
-            eprintln!("Error al escribir en el archivo de prueba: {:?}", e);
-            assert!(false);
-        }
-    }
-
-    match std::fs::remove_file(ruta) {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("Error al eliminar el archivo de prueba: {:?}", e);
-            assert!(false);
+            eprintln!("Error al cargar el laberinto: {:?}", e);
+            panic!();
         }
     }
 }
@@ -161,3 +144,532 @@ fn test_detonar_bomba_3() {
     let enemigo = &laberinto.grid[2][4].objeto;
     assert_eq!(enemigo, &Objeto::Vacio);
 }
+
+#[test]
+fn test_cargar_caracter_invalido() {
+    let ruta = "ejemplos/caracter_invalido.txt";
+
+    let error = Laberinto::cargar(ruta).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "línea 3, columna 6: carácter desconocido 'K'"
+    );
+}
+
+#[test]
+fn test_cargar_tamano_incorrecto() {
+    // Todas las filas tienen el mismo largo, así que es un tablero rectangular válido
+    let ruta = "ejemplos/tamano_incorrecto.txt";
+
+    let laberinto = Laberinto::cargar(ruta).unwrap();
+    assert_eq!(laberinto.ancho, 7);
+    assert_eq!(laberinto.alto, 3);
+}
+
+#[test]
+fn test_cargar_filas_irregulares() {
+    let ruta = "ejemplos/filas_irregulares.txt";
+
+    let error = Laberinto::cargar(ruta).err().unwrap();
+    assert!(matches!(
+        error,
+        LaberintoError::TableroIrregular { columnas: 7, ref filas_incorrectas }
+            if filas_incorrectas == &vec![(2, 6)]
+    ));
+}
+
+#[test]
+fn test_detonar_bomba_laberinto_rectangular() {
+    let ruta = "ejemplos/rectangular.txt";
+
+    let laberinto_result = Laberinto::cargar(ruta);
+    assert!(laberinto_result.is_ok());
+    let mut laberinto = laberinto_result.unwrap();
+    assert_eq!(laberinto.ancho, 9);
+    assert_eq!(laberinto.alto, 3);
+
+    let detonar_result = detonar_bomba(&mut laberinto, 8, 2);
+    assert!(detonar_result.is_ok());
+
+    let bomba = &laberinto.grid[2][8].objeto;
+    assert_eq!(bomba, &Objeto::Vacio);
+
+    // La explosión alcanza el enemigo de la primera fila
+    let enemigo = &laberinto.grid[0][8].objeto;
+    assert_eq!(enemigo, &Objeto::Vacio);
+
+    // Y se detiene en la roca de la última fila
+    let enemigo_protegido = &laberinto.grid[2][4].objeto;
+    assert_eq!(enemigo_protegido, &Objeto::Enemigo(1));
+
+    let fuera_de_rango = detonar_bomba(&mut laberinto, 9, 0);
+    assert!(fuera_de_rango.is_err());
+}
+
+#[test]
+fn test_from_reader_y_from_path_coinciden() {
+    let ruta = std::path::Path::new("ejemplos").join("ejemplo_4.txt");
+
+    let desde_archivo = Laberinto::from_path(&ruta).unwrap();
+    let contenido = std::fs::read_to_string(&ruta).unwrap();
+    let desde_lector = Laberinto::from_reader(std::io::Cursor::new(contenido)).unwrap();
+
+    assert_eq!(desde_archivo.grid, desde_lector.grid);
+
+    let inexistente = Laberinto::from_path("ejemplos/no_existe.txt");
+    assert!(matches!(inexistente, Err(LaberintoError::Io(_))));
+}
+
+#[test]
+fn test_predecir_detonacion_coincide_con_detonar() {
+    let ruta = "ejemplos/ejemplo_3.txt";
+
+    let laberinto = Laberinto::cargar(ruta).unwrap();
+    let original = laberinto.clone();
+
+    let (resultado, resumen) = laberinto.predecir_detonacion(0, 4).unwrap();
+    assert_eq!(laberinto, original);
+
+    let mut detonado = Laberinto::cargar(ruta).unwrap();
+    detonar_bomba(&mut detonado, 0, 4).unwrap();
+    assert_eq!(resultado, detonado);
+
+    assert_eq!(resumen.enemigos_eliminados, 1);
+    assert_eq!(resumen.enemigos_daniados, 1);
+    assert_eq!(resumen.bombas_encadenadas, 2);
+
+    assert!(laberinto.predecir_detonacion(7, 0).is_err());
+}
+
+// Laberinto vacío de `ancho` x `alto` con un desvío y una bomba de alcance 4
+fn laberinto_con_desvio(
+    ancho: usize,
+    alto: usize,
+    desvio: (usize, usize),
+    token: &str,
+    bomba: (usize, usize),
+) -> Laberinto {
+    let filas: Vec<String> = (0..alto)
+        .map(|y| {
+            let fila: Vec<&str> = (0..ancho)
+                .map(|x| match (x, y) {
+                    p if p == desvio => token,
+                    p if p == bomba => "B4",
+                    _ => "_",
+                })
+                .collect();
+            fila.join(" ")
+        })
+        .collect();
+    filas.join("\n").parse().unwrap()
+}
+
+#[test]
+fn test_desvio_en_el_borde_no_sale_del_laberinto() {
+    for (ancho, alto) in [(1, 1), (1, 4), (4, 1), (2, 2), (3, 4), (4, 3)] {
+        for desvio_y in 0..alto {
+            for desvio_x in 0..ancho {
+                for token in ["DL", "DR", "DU", "DD"] {
+                    for bomba_y in 0..alto {
+                        for bomba_x in 0..ancho {
+                            let desvio = (desvio_x, desvio_y);
+                            let bomba = (bomba_x, bomba_y);
+                            if bomba == desvio {
+                                continue;
+                            }
+                            let mut laberinto =
+                                laberinto_con_desvio(ancho, alto, desvio, token, bomba);
+                            let eventos =
+                                detonar_bomba_con_traza(&mut laberinto, bomba_x, bomba_y).unwrap();
+                            for evento in eventos {
+                                if let EventoExplosion::CeldaAlcanzada { x, y } = evento {
+                                    assert!(x < ancho && y < alto);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_desvio_hacia_afuera_detiene_la_explosion() {
+    let mut laberinto: Laberinto = "B2 DU\n_ F1".parse().unwrap();
+
+    let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+    assert!(eventos.contains(&EventoExplosion::Desviada {
+        x: 1,
+        y: 0,
+        direccion: bomberman_r::bomberman::model::direccion::Direccion::Arriba,
+    }));
+    assert!(!eventos.contains(&EventoExplosion::CeldaAlcanzada { x: 1, y: 1 }));
+    assert_eq!(laberinto.grid[1][1].objeto, Objeto::Enemigo(1));
+}
+
+#[test]
+fn test_desvios_consecutivos() {
+    let mut laberinto: Laberinto = "B2 DD _\n_ DR F1\n_ _ _".parse().unwrap();
+
+    let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+    let desvios: Vec<(usize, usize)> = eventos
+        .iter()
+        .filter_map(|e| match e {
+            EventoExplosion::Desviada { x, y, .. } => Some((*x, *y)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(desvios, vec![(1, 0), (1, 1)]);
+    assert_eq!(laberinto.grid[1][2].objeto, Objeto::Vacio);
+}
+
+#[test]
+fn test_pared_despues_de_un_desvio() {
+    let mut laberinto: Laberinto = "B3 DD\nF1 W\n_ F1".parse().unwrap();
+
+    let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+    assert!(eventos.contains(&EventoExplosion::Bloqueada {
+        x: 1,
+        y: 1,
+        objeto: Objeto::Pared,
+    }));
+    assert_eq!(laberinto.grid[2][1].objeto, Objeto::Enemigo(1));
+    assert_eq!(laberinto.grid[1][0].objeto, Objeto::Vacio);
+}
+
+#[test]
+fn test_desvios_enfrentados() {
+    let mut laberinto: Laberinto = "B3 DR DL F1".parse().unwrap();
+
+    let eventos = detonar_bomba_con_traza(&mut laberinto, 0, 0).unwrap();
+
+    assert!(eventos.contains(&EventoExplosion::CicloDeDesvio { x: 1, y: 0 }));
+    assert_eq!(laberinto.grid[0][3].objeto, Objeto::Enemigo(1));
+}
+
+fn ejecutar(args: &[&str]) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_bomberman_r"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn directorio_temporal(nombre: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("bomberman_r_{}_{}", nombre, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_cli_forma_posicional_y_detonar_coinciden() {
+    let dir = directorio_temporal("posicional");
+    let posicional = dir.join("posicional");
+    let subcomando = dir.join("subcomando");
+
+    let salida = ejecutar(&[
+        "ejemplos/ejemplo_1.txt",
+        posicional.to_str().unwrap(),
+        "0",
+        "0",
+    ]);
+    assert_eq!(salida.status.code(), Some(0));
+    let salida = ejecutar(&[
+        "detonar",
+        "ejemplos/ejemplo_1.txt",
+        subcomando.to_str().unwrap(),
+        "--bomba",
+        "0,0",
+    ]);
+    assert_eq!(salida.status.code(), Some(0));
+
+    let esperado = std::fs::read_to_string(posicional.join("ejemplo_1.txt")).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(subcomando.join("ejemplo_1.txt")).unwrap(),
+        esperado
+    );
+    assert!(esperado.starts_with("_ R R _ _ _ _"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_codigos_de_salida() {
+    let dir = directorio_temporal("codigos");
+    let dir_salida = dir.to_str().unwrap();
+
+    assert_eq!(ejecutar(&["--help"]).status.code(), Some(0));
+    assert_eq!(ejecutar(&["detonar", "--help"]).status.code(), Some(0));
+    assert_eq!(ejecutar(&[]).status.code(), Some(2));
+    assert_eq!(
+        ejecutar(&["ejemplos/ejemplo_1.txt", dir_salida, "0"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        ejecutar(&["detonar", "ejemplos/ejemplo_1.txt", dir_salida])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        ejecutar(&["mostrar", "ejemplos/ejemplo_1.txt", "--bomba", "a,0"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        ejecutar(&["validar", "ejemplos/ejemplo_1.txt", "--opcion"])
+            .status
+            .code(),
+        Some(2)
+    );
+
+    // El archivo de salida contiene el error, como antes
+    let salida = ejecutar(&[
+        "detonar",
+        "ejemplos/caracter_invalido.txt",
+        dir_salida,
+        "--bomba",
+        "0,0",
+    ]);
+    assert_eq!(salida.status.code(), Some(3));
+    let contenido = std::fs::read_to_string(dir.join("caracter_invalido.txt")).unwrap();
+    assert!(contenido.starts_with("ERROR:"));
+
+    assert_eq!(
+        ejecutar(&[
+            "detonar",
+            "ejemplos/ejemplo_1.txt",
+            dir_salida,
+            "--bomba",
+            "9,9"
+        ])
+        .status
+        .code(),
+        Some(4)
+    );
+    assert_eq!(
+        ejecutar(&[
+            "validar",
+            "ejemplos/ejemplo_1.txt",
+            "ejemplos/filas_irregulares.txt"
+        ])
+        .status
+        .code(),
+        Some(3)
+    );
+    assert_eq!(
+        ejecutar(&[
+            "generar",
+            dir.join("g.txt").to_str().unwrap(),
+            "--ancho",
+            "0"
+        ])
+        .status
+        .code(),
+        Some(6)
+    );
+    assert_eq!(
+        ejecutar(&[
+            "generar",
+            dir.join("g.txt").to_str().unwrap(),
+            "--densidad-paredes",
+            "0.5"
+        ])
+        .status
+        .code(),
+        Some(2)
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_mostrar_y_diff() {
+    let salida = ejecutar(&["mostrar", "ejemplos/ejemplo_1.txt", "--bomba", "0,0"]);
+    assert_eq!(salida.status.code(), Some(0));
+    let mostrado = String::from_utf8(salida.stdout).unwrap();
+    assert!(mostrado.starts_with("_ R R _ _ _ _\n"));
+
+    let salida = ejecutar(&["diff", "ejemplos/ejemplo_1.txt", "ejemplos/ejemplo_1.txt"]);
+    assert_eq!(salida.status.code(), Some(0));
+    assert!(salida.stdout.is_empty());
+
+    let salida = ejecutar(&["diff", "ejemplos/ejemplo_1.txt", "ejemplos/ejemplo_2.txt"]);
+    assert_eq!(salida.status.code(), Some(1));
+    assert!(String::from_utf8(salida.stdout)
+        .unwrap()
+        .contains("(0, 0): B2 -> _"));
+}
+
+#[test]
+fn test_cli_lote() {
+    let dir = directorio_temporal("lote");
+    let dir_salida = dir.join("salida");
+    let manifiesto = dir.join("manifiesto.txt");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        &manifiesto,
+        "# ejemplos\nejemplo_1.txt 0 0\nejemplo_2.txt 2 2 4 0\nejemplo_3.txt 9 9\n",
+    )
+    .unwrap();
+
+    let salida = ejecutar(&[
+        "lote",
+        "ejemplos/ejemplo_?.txt",
+        dir_salida.to_str().unwrap(),
+        "--manifiesto",
+        manifiesto.to_str().unwrap(),
+        "--hilos",
+        "3",
+    ]);
+    assert_eq!(salida.status.code(), Some(7));
+    let tabla = String::from_utf8(salida.stdout).unwrap();
+    assert!(tabla.contains("5 archivos: 2 correctos, 3 con errores"));
+    assert!(tabla
+        .lines()
+        .any(|l| l.starts_with("ejemplos/ejemplo_3.txt") && l.contains("error 4")));
+    assert!(tabla
+        .lines()
+        .any(|l| l.starts_with("ejemplos/ejemplo_4.txt")
+            && l.contains("error 7: no figura en el manifiesto")));
+
+    // Cada resultado coincide con el de una ejecución individual
+    let individual = dir.join("individual");
+    ejecutar(&[
+        "ejemplos/ejemplo_2.txt",
+        individual.to_str().unwrap(),
+        "2",
+        "2",
+        "4",
+        "0",
+    ]);
+    assert_eq!(
+        std::fs::read_to_string(dir_salida.join("ejemplo_2.txt")).unwrap(),
+        std::fs::read_to_string(individual.join("ejemplo_2.txt")).unwrap()
+    );
+    assert!(std::fs::read_to_string(dir_salida.join("ejemplo_3.txt"))
+        .unwrap()
+        .starts_with("ERROR:"));
+    assert!(!dir_salida.join("ejemplo_4.txt").exists());
+
+    // Las rutas que llevan al mismo archivo se procesan una sola vez
+    let salida = ejecutar(&[
+        "lote",
+        "ejemplos/ejemplo_1.txt",
+        "./ejemplos/ejemplo_1.txt",
+        dir_salida.to_str().unwrap(),
+        "--manifiesto",
+        manifiesto.to_str().unwrap(),
+    ]);
+    assert_eq!(salida.status.code(), Some(0));
+    assert!(String::from_utf8(salida.stdout)
+        .unwrap()
+        .contains("1 archivos: 1 correctos, 0 con errores"));
+
+    // Pero dos archivos distintos con el mismo nombre no se pueden distinguir
+    let copia = dir.join("ejemplo_1.txt");
+    std::fs::copy("ejemplos/ejemplo_1.txt", &copia).unwrap();
+    let salida = ejecutar(&[
+        "lote",
+        "ejemplos/ejemplo_1.txt",
+        copia.to_str().unwrap(),
+        dir_salida.to_str().unwrap(),
+        "--manifiesto",
+        manifiesto.to_str().unwrap(),
+    ]);
+    assert_eq!(salida.status.code(), Some(2));
+    assert!(String::from_utf8(salida.stderr)
+        .unwrap()
+        .contains("tienen el mismo nombre"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+fn ejecutar_con_entrada(args: &[&str], entrada: &str) -> std::process::Output {
+    use std::io::Write;
+
+    let mut proceso = std::process::Command::new(env!("CARGO_BIN_EXE_bomberman_r"))
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    proceso
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(entrada.as_bytes())
+        .unwrap();
+    proceso.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_cli_entrada_y_salida_estandar() {
+    let contenido = std::fs::read_to_string("ejemplos/ejemplo_1.txt").unwrap();
+
+    let salida = ejecutar_con_entrada(&["-", "-", "0", "0"], &contenido);
+    assert_eq!(salida.status.code(), Some(0));
+    let resultado = String::from_utf8(salida.stdout).unwrap();
+    assert!(resultado.starts_with("_ R R _ _ _ _\n"));
+    assert_eq!(resultado.lines().count(), 7);
+
+    // La salida de una ejecución sirve de entrada para la siguiente
+    let salida = ejecutar_con_entrada(&["detonar", "-", "-", "--bomba", "0,2"], &resultado);
+    assert_eq!(salida.status.code(), Some(0));
+    assert!(String::from_utf8(salida.stdout)
+        .unwrap()
+        .starts_with("_ R R _ _ _ _\n_ W R W _ W _\n_ _ _ _ _ _ _\n"));
+}
+
+#[test]
+fn test_cli_errores_en_la_salida_estandar() {
+    let salida = ejecutar_con_entrada(&["-", "-", "0", "0"], "B1 K1");
+    assert_eq!(salida.status.code(), Some(3));
+    assert!(String::from_utf8(salida.stdout)
+        .unwrap()
+        .starts_with("ERROR: Error al cargar el laberinto:"));
+
+    let salida = ejecutar_con_entrada(&["detonar", "-", "-", "--bomba", "5,5"], "B1 F1");
+    assert_eq!(salida.status.code(), Some(4));
+    assert_eq!(
+        String::from_utf8(salida.stdout).unwrap(),
+        "ERROR: Error al detonar la bomba: Fuera de los parámetros del laberinto\n"
+    );
+
+    // Con un archivo de entrada y salida estándar, el error también va a la salida estándar
+    let salida = ejecutar(&["ejemplos/caracter_invalido.txt", "-", "0", "0"]);
+    assert_eq!(salida.status.code(), Some(3));
+    assert!(String::from_utf8(salida.stdout)
+        .unwrap()
+        .starts_with("ERROR:"));
+}
+
+#[test]
+fn test_cli_generar_puzzle_usa_los_parametros_de_la_biblioteca() {
+    let dir = directorio_temporal("puzzle");
+    let archivo = dir.join("puzzle.txt");
+
+    let salida = ejecutar(&[
+        "generar",
+        archivo.to_str().unwrap(),
+        "--puzzle",
+        "--semilla",
+        "7",
+    ]);
+    assert_eq!(salida.status.code(), Some(0));
+
+    let esperado = generar_puzzle(&ParametrosPuzzle::default(), 7).unwrap();
+    assert_eq!(
+        Laberinto::cargar(archivo.to_str().unwrap()).unwrap(),
+        esperado.laberinto
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}